@@ -0,0 +1,133 @@
+#![no_main]
+
+//! Relayers call `resolve_execute_vaa_v1` with whatever VAA they observed, so
+//! it must fail with an error rather than panic on any input.
+
+use std::sync::Once;
+
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program_option::COption,
+        program_pack::Pack,
+        program_stubs::{set_syscall_stubs, SyscallStubs},
+    },
+    InstructionData,
+};
+use anchor_spl::token::spl_token::state::Mint;
+use libfuzzer_sys::fuzz_target;
+use token_bridge_relayer::{
+    instruction::ResolveExecuteVaaV1,
+    state::{LUT, SEED_PREFIX_LUT},
+    utils::token_bridge_pda,
+    ID, OUR_CHAIN,
+};
+use wormhole_raw_vaas::{token_bridge::TokenBridgePayload, Body};
+
+/// Drops program logs, which would otherwise flood the fuzzer's output.
+struct QuietStubs;
+
+impl SyscallStubs for QuietStubs {
+    fn sol_log(&self, _message: &str) {}
+}
+
+fn resolve<'info>(vaa_body: &[u8], accounts: &'info [AccountInfo<'info>]) {
+    let data = ResolveExecuteVaaV1 {
+        vaa_body: vaa_body.to_vec(),
+    }
+    .data();
+    let _ = token_bridge_relayer::entry(&ID, accounts, &data);
+}
+
+/// Returns the mint and claim account the resolver asks for, if the body is
+/// a transfer.
+fn transfer_accounts(vaa_body: &[u8]) -> Option<(Pubkey, Pubkey)> {
+    let body = Body::parse(vaa_body).ok()?;
+    let payload = TokenBridgePayload::try_from(body.payload()).ok()?.message();
+    let transfer = payload.transfer_with_message()?;
+    let mint = if transfer.token_chain() == OUR_CHAIN {
+        Pubkey::new_from_array(transfer.token_address())
+    } else {
+        token_bridge_pda(&[
+            b"wrapped",
+            &transfer.token_chain().to_be_bytes(),
+            &transfer.token_address(),
+        ])
+    };
+    let claim = token_bridge_pda(&[
+        &body.emitter_address(),
+        &body.emitter_chain().to_be_bytes(),
+        &body.sequence().to_be_bytes(),
+    ]);
+    Some((mint, claim))
+}
+
+fuzz_target!(|vaa_body: Vec<u8>| {
+    static STUBS: Once = Once::new();
+    STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(QuietStubs));
+    });
+
+    // Without accounts, the resolver parses the VAA and asks for the mint.
+    resolve(&vaa_body, &[]);
+
+    // With the mint, LUT and an unclaimed transfer, it goes on to build the
+    // instruction.
+    let Some((mint, claim)) = transfer_accounts(&vaa_body) else {
+        return;
+    };
+    let mut mint_lamports = 0;
+    let mut mint_data = vec![0; Mint::LEN];
+    Mint {
+        mint_authority: COption::None,
+        supply: 0,
+        decimals: 8,
+        is_initialized: true,
+        freeze_authority: COption::None,
+    }
+    .pack_into_slice(&mut mint_data);
+    let lut_pointer = Pubkey::find_program_address(&[SEED_PREFIX_LUT], &ID).0;
+    let mut lut_lamports = 0;
+    let mut lut_data = Vec::new();
+    LUT {
+        bump: 0,
+        address: Pubkey::default(),
+    }
+    .try_serialize(&mut lut_data)
+    .unwrap();
+    let mut claim_lamports = 0;
+    let mut claim_data = Vec::new();
+    let accounts = [
+        AccountInfo::new(
+            &mint,
+            false,
+            false,
+            &mut mint_lamports,
+            &mut mint_data,
+            &anchor_spl::token::ID,
+            false,
+            0,
+        ),
+        AccountInfo::new(
+            &lut_pointer,
+            false,
+            false,
+            &mut lut_lamports,
+            &mut lut_data,
+            &ID,
+            false,
+            0,
+        ),
+        AccountInfo::new(
+            &claim,
+            false,
+            false,
+            &mut claim_lamports,
+            &mut claim_data,
+            &anchor_lang::system_program::ID,
+            false,
+            0,
+        ),
+    ];
+    resolve(&vaa_body, &accounts);
+});
@@ -153,4 +153,101 @@ pub enum TokenBridgeRelayerError {
     #[msg("FailedToParseVaaBody")]
     /// Failed to parse the VAA body.
     FailedToParseVaaBody,
+
+    #[msg("InvalidTokenBridgeCustody")]
+    /// Specified Token Bridge custody PDA is wrong.
+    InvalidTokenBridgeCustody,
+
+    #[msg("InvalidTokenBridgeWrappedMeta")]
+    /// Specified Token Bridge wrapped meta PDA is wrong.
+    InvalidTokenBridgeWrappedMeta,
+
+    #[msg("InvalidTokenBridgeClaim")]
+    /// Specified Token Bridge claim PDA is wrong.
+    InvalidTokenBridgeClaim,
+
+    #[msg("FromTokenAccountRequired")]
+    /// A source token account is required unless wrapping native SOL.
+    FromTokenAccountRequired,
+
+    #[msg("InvalidSignedQuote")]
+    /// Signed quote could not be parsed.
+    InvalidSignedQuote,
+
+    #[msg("InvalidQuoteSourceChain")]
+    /// Signed quote is not for this chain.
+    InvalidQuoteSourceChain,
+
+    #[msg("InvalidQuoteDestinationChain")]
+    /// Signed quote is not for the recipient chain.
+    InvalidQuoteDestinationChain,
+
+    #[msg("QuoteExpired")]
+    /// Signed quote has expired.
+    QuoteExpired,
+
+    #[msg("InvalidQuotePayee")]
+    /// Payee account does not match the signed quote.
+    InvalidQuotePayee,
+
+    #[msg("ZeroGasLimit")]
+    /// Relay instructions must request a non-zero gas limit.
+    ZeroGasLimit,
+
+    #[msg("InvalidDropOffRecipient")]
+    /// Gas drop-off recipient must be the transfer recipient.
+    InvalidDropOffRecipient,
+
+    #[msg("InvalidRelayInstructions")]
+    /// Relay instructions could not be parsed.
+    InvalidRelayInstructions,
+
+    #[msg("ExecAmountExceedsMax")]
+    /// Execution amount derived from the quote exceeds the allowed maximum.
+    ExecAmountExceedsMax,
+
+    #[msg("InvalidWormholeMessage")]
+    /// Wormhole message must be a signer or the message PDA.
+    InvalidWormholeMessage,
+
+    #[msg("TokenBridgeCustodyRequired")]
+    /// Token Bridge custody accounts are required for native mints.
+    TokenBridgeCustodyRequired,
+
+    #[msg("TokenBridgeWrappedMetaRequired")]
+    /// Token Bridge wrapped meta is required for wrapped mints.
+    TokenBridgeWrappedMetaRequired,
+
+    #[msg("InvalidPostedMessage")]
+    /// Posted message is not a Token Bridge transfer with the given sequence.
+    InvalidPostedMessage,
+
+    #[msg("TransferNotFromThisProgram")]
+    /// Posted transfer was not sent through this program.
+    TransferNotFromThisProgram,
+
+    #[msg("InvalidTransferReceiptSender")]
+    /// Only the sender can close a transfer receipt.
+    InvalidTransferReceiptSender,
+
+    #[msg("InsufficientAmountOut")]
+    /// Bridged amount is less than the requested minimum.
+    InsufficientAmountOut,
+
+    #[msg("UnsupportedMint")]
+    /// Mint is not an SPL Token mint, which is all the Token Bridge supports.
+    UnsupportedMint,
+
+    #[msg("InvalidRelayerMessage")]
+    /// Token Bridge payload is not a valid `TokenBridgeRelayerMessage`.
+    InvalidRelayerMessage,
+
+    #[msg("InvalidQuoteDecimals")]
+    /// Destination gas price or native decimals exceed what the quote
+    /// estimate supports.
+    InvalidQuoteDecimals,
+
+    #[msg("InvalidDeliveryReceiptPayer")]
+    /// Only the payer can close a delivery receipt.
+    InvalidDeliveryReceiptPayer,
 }
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+
+#[event]
+/// Emitted when tokens are sent to a foreign chain via the Token Bridge and
+/// execution of the resulting VAA has been requested from the Executor.
+pub struct TransferInitiated {
+    /// Mint of the bridged token.
+    pub mint: Pubkey,
+    /// Amount requested by the sender.
+    pub amount: u64,
+    /// Amount actually bridged after the Token Bridge 8 decimal truncation.
+    pub truncated_amount: u64,
+    /// Wormhole chain ID of the target chain.
+    pub recipient_chain: u16,
+    /// Address of the target wallet on the target chain.
+    pub recipient_address: [u8; 32],
+    /// Token Bridge payload 3 recipient.
+    pub dst_transfer_recipient: [u8; 32],
+    /// Executor destination address.
+    pub dst_execution_address: [u8; 32],
+    /// Lamports paid to the execution payee.
+    pub exec_amount: u64,
+    /// Token Bridge emitter of the resulting VAA.
+    pub emitter: Pubkey,
+    /// Sequence of the resulting VAA.
+    pub sequence: u64,
+    /// Payer of the transfer.
+    pub payer: Pubkey,
+}
+
+#[event]
+/// Emitted when a Token Bridge transfer addressed to this program has been
+/// redeemed and delivered to its intended recipient.
+pub struct TransferRedeemed {
+    /// Wormhole chain ID of the VAA emitter.
+    pub emitter_chain: u16,
+    /// Address of the VAA emitter.
+    pub emitter_address: [u8; 32],
+    /// Sequence of the VAA.
+    pub sequence: u64,
+    /// Mint of the delivered token.
+    pub mint: Pubkey,
+    /// Amount delivered to the recipient.
+    pub amount: u64,
+    /// Recipient encoded in the transfer payload.
+    pub recipient: Pubkey,
+    /// Whether WSOL was unwrapped and delivered as lamports.
+    pub unwrapped: bool,
+    /// Payer of the redemption, i.e. the relayer.
+    pub payer: Pubkey,
+}
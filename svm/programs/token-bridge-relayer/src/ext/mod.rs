@@ -15,5 +15,11 @@ pub fn make_vaa_v1_request(chain: u16, address: [u8; 32], sequence: u64) -> Vec<
 }
 
 mod instructions;
+mod posted_message;
+mod relay_instructions;
+mod signed_quote;
 
 pub use instructions::*;
+pub use posted_message::*;
+pub use relay_instructions::*;
+pub use signed_quote::*;
@@ -0,0 +1,176 @@
+const POSTED_MESSAGE_PREFIX: &[u8; 3] = b"msg";
+const TOKEN_BRIDGE_TRANSFER_WITH_PAYLOAD: u8 = 3;
+
+const SEQUENCE_OFFSET: usize = {
+    3 // prefix
+    + 1 // vaa version
+    + 1 // consistency level
+    + 4 // vaa time
+    + 32 // vaa signature account
+    + 4 // submission time
+    + 4 // nonce
+};
+const PAYLOAD_OFFSET: usize = {
+    SEQUENCE_OFFSET
+    + 8 // sequence
+    + 2 // emitter chain
+    + 32 // emitter address
+    + 4 // payload length
+};
+const TRANSFER_LEN: usize = {
+    1 // payload id
+    + 32 // amount
+    + 32 // token address
+    + 2 // token chain
+    + 32 // to
+    + 2 // to chain
+    + 32 // from address
+};
+
+/// Token Bridge transfer with payload, read from a Core Bridge posted message
+/// account (`PostedMessageV1`). The account itself is little-endian borsh,
+/// the Token Bridge payload is big-endian.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostedTransferWithPayload {
+    pub sequence: u64,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub to: [u8; 32],
+    pub to_chain: u16,
+    pub from_address: [u8; 32],
+    /// Payload of the transfer, i.e. the `TokenBridgeRelayerMessage`.
+    pub payload: Vec<u8>,
+}
+
+impl PostedTransferWithPayload {
+    /// Parses a posted message account, returning `None` if it is not a
+    /// Token Bridge transfer with payload.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.get(..3)? != POSTED_MESSAGE_PREFIX {
+            return None;
+        }
+        let payload_len = u32::from_le_bytes(
+            data.get(PAYLOAD_OFFSET - 4..PAYLOAD_OFFSET)?
+                .try_into()
+                .ok()?,
+        );
+        let payload =
+            data.get(PAYLOAD_OFFSET..PAYLOAD_OFFSET.checked_add(payload_len as usize)?)?;
+        if payload.len() < TRANSFER_LEN || payload[0] != TOKEN_BRIDGE_TRANSFER_WITH_PAYLOAD {
+            return None;
+        }
+        Some(Self {
+            sequence: u64::from_le_bytes(
+                data[SEQUENCE_OFFSET..SEQUENCE_OFFSET + 8]
+                    .try_into()
+                    .unwrap(),
+            ),
+            emitter_chain: u16::from_le_bytes(
+                data[SEQUENCE_OFFSET + 8..SEQUENCE_OFFSET + 10]
+                    .try_into()
+                    .unwrap(),
+            ),
+            emitter_address: data[SEQUENCE_OFFSET + 10..SEQUENCE_OFFSET + 42]
+                .try_into()
+                .unwrap(),
+            to: payload[67..99].try_into().unwrap(),
+            to_chain: u16::from_be_bytes(payload[99..101].try_into().unwrap()),
+            from_address: payload[101..133].try_into().unwrap(),
+            payload: payload[TRANSFER_LEN..].to_vec(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer() -> PostedTransferWithPayload {
+        PostedTransferWithPayload {
+            sequence: 42,
+            emitter_chain: 1,
+            emitter_address: [1; 32],
+            to: [2; 32],
+            to_chain: 10002,
+            from_address: [3; 32],
+            payload: vec![4; 32],
+        }
+    }
+
+    fn encode(transfer: &PostedTransferWithPayload) -> Vec<u8> {
+        let mut payload = vec![TOKEN_BRIDGE_TRANSFER_WITH_PAYLOAD];
+        payload.extend_from_slice(&[0; 32]); // amount
+        payload.extend_from_slice(&[0; 32]); // token address
+        payload.extend_from_slice(&1u16.to_be_bytes()); // token chain
+        payload.extend_from_slice(&transfer.to);
+        payload.extend_from_slice(&transfer.to_chain.to_be_bytes());
+        payload.extend_from_slice(&transfer.from_address);
+        payload.extend_from_slice(&transfer.payload);
+
+        let mut out = POSTED_MESSAGE_PREFIX.to_vec();
+        out.resize(SEQUENCE_OFFSET, 0);
+        out.extend_from_slice(&transfer.sequence.to_le_bytes());
+        out.extend_from_slice(&transfer.emitter_chain.to_le_bytes());
+        out.extend_from_slice(&transfer.emitter_address);
+        out.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        out.extend_from_slice(&payload);
+        out
+    }
+
+    #[test]
+    fn parse_round_trips() {
+        let transfer = transfer();
+        assert_eq!(
+            PostedTransferWithPayload::parse(&encode(&transfer)),
+            Some(transfer)
+        );
+    }
+
+    #[test]
+    fn parse_accepts_empty_payload() {
+        let transfer = PostedTransferWithPayload {
+            payload: vec![],
+            ..transfer()
+        };
+        assert_eq!(
+            PostedTransferWithPayload::parse(&encode(&transfer)),
+            Some(transfer)
+        );
+    }
+
+    #[test]
+    fn parse_rejects_truncated_message() {
+        let data = encode(&transfer());
+        assert_eq!(
+            PostedTransferWithPayload::parse(&data[..data.len() - 1]),
+            None
+        );
+        assert_eq!(
+            PostedTransferWithPayload::parse(&data[..PAYLOAD_OFFSET]),
+            None
+        );
+        assert_eq!(PostedTransferWithPayload::parse(&data[..2]), None);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_prefix() {
+        let mut data = encode(&transfer());
+        data[..3].copy_from_slice(b"msu");
+        assert_eq!(PostedTransferWithPayload::parse(&data), None);
+    }
+
+    #[test]
+    fn parse_rejects_transfer_without_payload() {
+        let mut data = encode(&transfer());
+        data[PAYLOAD_OFFSET] = 1;
+        assert_eq!(PostedTransferWithPayload::parse(&data), None);
+    }
+
+    #[test]
+    fn parse_rejects_short_transfer() {
+        let mut data = encode(&transfer());
+        let len = (TRANSFER_LEN - 1) as u32;
+        data[PAYLOAD_OFFSET - 4..PAYLOAD_OFFSET].copy_from_slice(&len.to_le_bytes());
+        assert_eq!(PostedTransferWithPayload::parse(&data), None);
+    }
+}
@@ -0,0 +1,205 @@
+use anchor_lang::prelude::*;
+
+const RELAY_IX_TYPE_GAS: u8 = 1;
+const RELAY_IX_TYPE_DROP_OFF: u8 = 2;
+
+/// Type, gas limit and msg value.
+const RELAY_IX_GAS_LEN: usize = 1 + 16 + 16;
+/// Type, drop off and recipient.
+const RELAY_IX_DROP_OFF_LEN: usize = 1 + 16 + 32;
+
+/// Executor relay instruction. Encoded as a type byte followed by big-endian
+/// fields, and concatenated with no length prefix.
+#[derive(AnchorSerialize, AnchorDeserialize, Debug, Clone, PartialEq, Eq)]
+pub enum RelayInstruction {
+    /// Gas limit and native value for the destination execution.
+    Gas { gas_limit: u128, msg_value: u128 },
+    /// Native gas dropped off to a recipient on the destination chain.
+    GasDropOff { drop_off: u128, recipient: [u8; 32] },
+}
+
+impl RelayInstruction {
+    /// Length of the encoded instruction, including its type byte.
+    pub const fn encoded_len(&self) -> usize {
+        match self {
+            RelayInstruction::Gas { .. } => RELAY_IX_GAS_LEN,
+            RelayInstruction::GasDropOff { .. } => RELAY_IX_DROP_OFF_LEN,
+        }
+    }
+}
+
+pub fn encode_relay_instructions(relay_instructions: &[RelayInstruction]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(
+        relay_instructions
+            .iter()
+            .map(RelayInstruction::encoded_len)
+            .sum(),
+    );
+    for relay_instruction in relay_instructions {
+        match relay_instruction {
+            RelayInstruction::Gas {
+                gas_limit,
+                msg_value,
+            } => {
+                out.push(RELAY_IX_TYPE_GAS);
+                out.extend_from_slice(&gas_limit.to_be_bytes());
+                out.extend_from_slice(&msg_value.to_be_bytes());
+            }
+            RelayInstruction::GasDropOff {
+                drop_off,
+                recipient,
+            } => {
+                out.push(RELAY_IX_TYPE_DROP_OFF);
+                out.extend_from_slice(&drop_off.to_be_bytes());
+                out.extend_from_slice(recipient);
+            }
+        }
+    }
+    out
+}
+
+/// Decodes concatenated relay instructions, returning `None` on an unknown
+/// type or trailing bytes.
+pub fn decode_relay_instructions(mut data: &[u8]) -> Option<Vec<RelayInstruction>> {
+    let mut out = Vec::new();
+    while let Some((&ix_type, rest)) = data.split_first() {
+        let (a, rest) = rest.split_first_chunk::<16>()?;
+        out.push(match ix_type {
+            RELAY_IX_TYPE_GAS => {
+                let (b, rest) = rest.split_first_chunk::<16>()?;
+                data = rest;
+                RelayInstruction::Gas {
+                    gas_limit: u128::from_be_bytes(*a),
+                    msg_value: u128::from_be_bytes(*b),
+                }
+            }
+            RELAY_IX_TYPE_DROP_OFF => {
+                let (recipient, rest) = rest.split_first_chunk::<32>()?;
+                data = rest;
+                RelayInstruction::GasDropOff {
+                    drop_off: u128::from_be_bytes(*a),
+                    recipient: *recipient,
+                }
+            }
+            _ => return None,
+        });
+    }
+    Some(out)
+}
+
+/// Sums the gas limit and the native value needed on the destination chain,
+/// which includes both msg values and drop-offs. Returns `None` on overflow.
+pub fn total_gas_limit_and_msg_value(
+    relay_instructions: &[RelayInstruction],
+) -> Option<(u128, u128)> {
+    let mut gas_limit: u128 = 0;
+    let mut msg_value: u128 = 0;
+    for relay_instruction in relay_instructions {
+        match relay_instruction {
+            RelayInstruction::Gas {
+                gas_limit: limit,
+                msg_value: value,
+            } => {
+                gas_limit = gas_limit.checked_add(*limit)?;
+                msg_value = msg_value.checked_add(*value)?;
+            }
+            RelayInstruction::GasDropOff { drop_off, .. } => {
+                msg_value = msg_value.checked_add(*drop_off)?;
+            }
+        }
+    }
+    Some((gas_limit, msg_value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn gas() -> RelayInstruction {
+        RelayInstruction::Gas {
+            gas_limit: 250_000,
+            msg_value: 1,
+        }
+    }
+
+    fn drop_off() -> RelayInstruction {
+        RelayInstruction::GasDropOff {
+            drop_off: 2,
+            recipient: [1; 32],
+        }
+    }
+
+    #[test]
+    fn gas_round_trips() {
+        let data = encode_relay_instructions(&[gas()]);
+        assert_eq!(data.len(), RELAY_IX_GAS_LEN);
+        assert_eq!(data[0], RELAY_IX_TYPE_GAS);
+        assert_eq!(decode_relay_instructions(&data), Some(vec![gas()]));
+    }
+
+    #[test]
+    fn drop_off_round_trips() {
+        let data = encode_relay_instructions(&[drop_off()]);
+        assert_eq!(data.len(), RELAY_IX_DROP_OFF_LEN);
+        assert_eq!(data[0], RELAY_IX_TYPE_DROP_OFF);
+        assert_eq!(decode_relay_instructions(&data), Some(vec![drop_off()]));
+    }
+
+    #[test]
+    fn concatenated_instructions_round_trip() {
+        let relay_instructions = vec![gas(), drop_off(), gas()];
+        let data = encode_relay_instructions(&relay_instructions);
+        assert_eq!(decode_relay_instructions(&data), Some(relay_instructions));
+        assert_eq!(decode_relay_instructions(&[]), Some(vec![]));
+    }
+
+    #[test]
+    fn encode_allocates_exact_capacity() {
+        let data = encode_relay_instructions(&[gas(), drop_off()]);
+        assert_eq!(data.len(), RELAY_IX_GAS_LEN + RELAY_IX_DROP_OFF_LEN);
+        assert_eq!(data.capacity(), data.len());
+    }
+
+    #[test]
+    fn decode_rejects_truncated_instructions() {
+        let data = encode_relay_instructions(&[gas(), drop_off()]);
+        assert_eq!(decode_relay_instructions(&data[..data.len() - 1]), None);
+        assert_eq!(
+            decode_relay_instructions(&data[..RELAY_IX_GAS_LEN - 1]),
+            None
+        );
+        assert_eq!(decode_relay_instructions(&data[..1]), None);
+    }
+
+    #[test]
+    fn decode_rejects_unknown_type() {
+        let mut data = encode_relay_instructions(&[gas()]);
+        data[0] = 0;
+        assert_eq!(decode_relay_instructions(&data), None);
+        data[0] = 3;
+        assert_eq!(decode_relay_instructions(&data), None);
+    }
+
+    #[test]
+    fn totals_sum_msg_values_and_drop_offs() {
+        assert_eq!(
+            total_gas_limit_and_msg_value(&[gas(), drop_off(), gas()]),
+            Some((500_000, 4))
+        );
+        assert_eq!(total_gas_limit_and_msg_value(&[]), Some((0, 0)));
+    }
+
+    #[test]
+    fn totals_reject_overflow() {
+        let max_gas = RelayInstruction::Gas {
+            gas_limit: u128::MAX,
+            msg_value: 0,
+        };
+        assert_eq!(total_gas_limit_and_msg_value(&[max_gas, gas()]), None);
+        let max_drop_off = RelayInstruction::GasDropOff {
+            drop_off: u128::MAX,
+            recipient: [1; 32],
+        };
+        assert_eq!(total_gas_limit_and_msg_value(&[max_drop_off, gas()]), None);
+    }
+}
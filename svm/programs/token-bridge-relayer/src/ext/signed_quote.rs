@@ -0,0 +1,214 @@
+const QUOTE_PREFIX_EQ01: &[u8; 4] = b"EQ01";
+
+/// Decimals of the prices and base fee in a quote.
+const QUOTE_DECIMALS: u8 = 10;
+
+/// Decimals used for intermediate values when estimating the cost. This is
+/// also the most decimals a destination gas price or native token may have,
+/// so that normalizing never drops precision.
+pub const NORMALIZED_DECIMALS: u8 = 18;
+
+/// Decimals of lamports.
+const SOL_DECIMALS: u8 = 9;
+
+const SIGNED_QUOTE_LEN: usize = {
+    4 // prefix
+    + 20 // quoter
+    + 32 // payee
+    + 2 // source chain
+    + 2 // destination chain
+    + 8 // expiry time
+    + 8 // base fee
+    + 8 // destination gas price
+    + 8 // source price
+    + 8 // destination price
+    + 65 // signature
+};
+
+/// Executor signed quote (`EQ01`). All integers are big-endian.
+///
+/// The signature is not verified here since the Executor does not verify it
+/// on-chain either. It is only checked by the relayer picking up the request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedQuote {
+    pub quoter: [u8; 20],
+    pub payee: [u8; 32],
+    pub src_chain: u16,
+    pub dst_chain: u16,
+    /// Unix timestamp in seconds after which the quote is no longer valid.
+    pub expiry_time: u64,
+    pub base_fee: u64,
+    pub dst_gas_price: u64,
+    pub src_price: u64,
+    pub dst_price: u64,
+    pub signature: [u8; 65],
+}
+
+impl SignedQuote {
+    /// Parses an `EQ01` signed quote, returning `None` if the prefix or length
+    /// is wrong.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() != SIGNED_QUOTE_LEN || &data[..4] != QUOTE_PREFIX_EQ01 {
+            return None;
+        }
+        let u16_at = |i: usize| u16::from_be_bytes(data[i..i + 2].try_into().unwrap());
+        let u64_at = |i: usize| u64::from_be_bytes(data[i..i + 8].try_into().unwrap());
+        Some(Self {
+            quoter: data[4..24].try_into().unwrap(),
+            payee: data[24..56].try_into().unwrap(),
+            src_chain: u16_at(56),
+            dst_chain: u16_at(58),
+            expiry_time: u64_at(60),
+            base_fee: u64_at(68),
+            dst_gas_price: u64_at(76),
+            src_price: u64_at(84),
+            dst_price: u64_at(92),
+            signature: data[100..165].try_into().unwrap(),
+        })
+    }
+
+    /// Estimates the lamports the Executor expects for a request with the
+    /// given total gas limit and msg value (including drop-offs), mirroring
+    /// the off-chain quote estimate. Returns `None` on overflow, a zero
+    /// source price or destination decimals above [`NORMALIZED_DECIMALS`].
+    pub fn estimate_cost(
+        &self,
+        gas_limit: u128,
+        msg_value: u128,
+        dst_gas_price_decimals: u8,
+        dst_native_decimals: u8,
+    ) -> Option<u128> {
+        if dst_gas_price_decimals > NORMALIZED_DECIMALS || dst_native_decimals > NORMALIZED_DECIMALS
+        {
+            return None;
+        }
+        let one = 10u128.checked_pow(NORMALIZED_DECIMALS.into())?;
+        // Value of one destination native token in source native tokens. Both
+        // prices have the same decimals, so they are divided as is rather than
+        // normalized first, which would overflow for ordinary prices.
+        let conversion = u128::from(self.dst_price)
+            .checked_mul(one)?
+            .checked_div(self.src_price.into())?;
+
+        let base_fee = normalize(self.base_fee.into(), QUOTE_DECIMALS, SOL_DECIMALS)?;
+        let gas_cost = normalize(
+            gas_limit.checked_mul(self.dst_gas_price.into())?,
+            dst_gas_price_decimals,
+            NORMALIZED_DECIMALS,
+        )?;
+        let gas_cost = normalize(
+            gas_cost.checked_mul(conversion)? / one,
+            NORMALIZED_DECIMALS,
+            SOL_DECIMALS,
+        )?;
+        let msg_value = normalize(msg_value, dst_native_decimals, NORMALIZED_DECIMALS)?;
+        let msg_value = normalize(
+            msg_value.checked_mul(conversion)? / one,
+            NORMALIZED_DECIMALS,
+            SOL_DECIMALS,
+        )?;
+        base_fee.checked_add(gas_cost)?.checked_add(msg_value)
+    }
+}
+
+fn normalize(amount: u128, from: u8, to: u8) -> Option<u128> {
+    if from > to {
+        Some(amount / 10u128.checked_pow((from - to).into())?)
+    } else {
+        amount.checked_mul(10u128.checked_pow((to - from).into())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// $150 SOL and $3000 ETH, with prices at 10 decimals.
+    fn quote(base_fee: u64, dst_gas_price: u64, dst_price: u64) -> SignedQuote {
+        SignedQuote {
+            quoter: [0; 20],
+            payee: [0; 32],
+            src_chain: 1,
+            dst_chain: 2,
+            expiry_time: u64::MAX,
+            base_fee,
+            dst_gas_price,
+            src_price: 1_500_000_000_000,
+            dst_price,
+            signature: [0; 65],
+        }
+    }
+
+    fn encode(quote: &SignedQuote) -> Vec<u8> {
+        let mut out = QUOTE_PREFIX_EQ01.to_vec();
+        out.extend_from_slice(&quote.quoter);
+        out.extend_from_slice(&quote.payee);
+        out.extend_from_slice(&quote.src_chain.to_be_bytes());
+        out.extend_from_slice(&quote.dst_chain.to_be_bytes());
+        out.extend_from_slice(&quote.expiry_time.to_be_bytes());
+        out.extend_from_slice(&quote.base_fee.to_be_bytes());
+        out.extend_from_slice(&quote.dst_gas_price.to_be_bytes());
+        out.extend_from_slice(&quote.src_price.to_be_bytes());
+        out.extend_from_slice(&quote.dst_price.to_be_bytes());
+        out.extend_from_slice(&quote.signature);
+        out
+    }
+
+    #[test]
+    fn parse_round_trips() {
+        let quote = quote(1, 2, 3);
+        assert_eq!(SignedQuote::parse(&encode(&quote)), Some(quote));
+    }
+
+    #[test]
+    fn parse_rejects_truncated_quote() {
+        let data = encode(&quote(1, 2, 3));
+        assert_eq!(SignedQuote::parse(&data[..SIGNED_QUOTE_LEN - 1]), None);
+        assert_eq!(SignedQuote::parse(&data[..4]), None);
+    }
+
+    #[test]
+    fn parse_rejects_unknown_prefix() {
+        let mut data = encode(&quote(1, 2, 3));
+        data[..4].copy_from_slice(b"EQ02");
+        assert_eq!(SignedQuote::parse(&data), None);
+    }
+
+    // Expected values are from the Executor SDK's `estimateQuote`.
+
+    #[test]
+    fn estimate_cost_to_evm() {
+        // 0.001 SOL base fee and 250k gas at 1 gwei.
+        let quote = quote(10_000_000, 1_000_000_000, 30_000_000_000_000);
+        assert_eq!(quote.estimate_cost(250_000, 0, 18, 18), Some(6_000_000));
+        // Plus a 0.01 ETH drop-off.
+        assert_eq!(
+            quote.estimate_cost(250_000, 10_000_000_000_000_000, 18, 18),
+            Some(206_000_000)
+        );
+    }
+
+    #[test]
+    fn estimate_cost_to_sui() {
+        // $3.50 SUI, 5M gas at 750 MIST and a 1 SUI drop-off.
+        let quote = quote(0, 750, 35_000_000_000);
+        assert_eq!(
+            quote.estimate_cost(5_000_000, 1_000_000_000, 9, 9),
+            Some(110_833_332)
+        );
+    }
+
+    #[test]
+    fn estimate_cost_rejects_unsupported_decimals() {
+        let quote = quote(0, 1, 30_000_000_000_000);
+        assert_eq!(quote.estimate_cost(1, 0, NORMALIZED_DECIMALS + 1, 18), None);
+        assert_eq!(quote.estimate_cost(1, 0, 18, NORMALIZED_DECIMALS + 1), None);
+    }
+
+    #[test]
+    fn estimate_cost_rejects_zero_source_price() {
+        let mut quote = quote(0, 1, 30_000_000_000_000);
+        quote.src_price = 0;
+        assert_eq!(quote.estimate_cost(1, 0, 18, 18), None);
+    }
+}
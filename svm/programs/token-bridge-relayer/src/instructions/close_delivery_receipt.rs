@@ -0,0 +1,29 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::TokenBridgeRelayerError, state::DeliveryReceipt};
+
+#[derive(Accounts)]
+pub struct CloseDeliveryReceipt<'info> {
+    #[account(mut)]
+    /// Payer of the redemption, who receives the receipt rent.
+    pub payer: Signer<'info>,
+
+    #[account(
+        mut,
+        close = payer,
+        has_one = payer @ TokenBridgeRelayerError::InvalidDeliveryReceiptPayer,
+        seeds = [
+            DeliveryReceipt::SEED_PREFIX,
+            &delivery_receipt.emitter_chain.to_be_bytes(),
+            delivery_receipt.emitter_address.as_ref(),
+            &delivery_receipt.sequence.to_be_bytes(),
+        ],
+        bump = delivery_receipt.bump,
+    )]
+    /// Delivery receipt to close.
+    pub delivery_receipt: Account<'info, DeliveryReceipt>,
+}
+
+pub fn close_delivery_receipt(_ctx: Context<CloseDeliveryReceipt>) -> Result<()> {
+    Ok(())
+}
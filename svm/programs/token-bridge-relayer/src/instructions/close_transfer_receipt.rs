@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::{error::TokenBridgeRelayerError, state::TransferReceipt};
+
+#[derive(Accounts)]
+pub struct CloseTransferReceipt<'info> {
+    #[account(mut)]
+    /// Sender of the transfer, who receives the receipt rent.
+    pub sender: Signer<'info>,
+
+    #[account(
+        mut,
+        close = sender,
+        has_one = sender @ TokenBridgeRelayerError::InvalidTransferReceiptSender,
+        seeds = [
+            TransferReceipt::SEED_PREFIX,
+            &transfer_receipt.sequence.to_be_bytes(),
+        ],
+        bump = transfer_receipt.bump,
+    )]
+    /// Transfer receipt to close.
+    pub transfer_receipt: Account<'info, TransferReceipt>,
+}
+
+pub fn close_transfer_receipt(_ctx: Context<CloseTransferReceipt>) -> Result<()> {
+    Ok(())
+}
@@ -1,12 +1,59 @@
 mod native;
+mod unified;
 mod wrapped;
 
 pub use native::*;
+pub use unified::*;
 pub use wrapped::*;
 
-use crate::state::RedeemerConfig;
+use crate::{
+    error::TokenBridgeRelayerError,
+    events::TransferRedeemed,
+    message::TokenBridgeRelayerMessage,
+    state::{DeliveryReceipt, RedeemerConfig},
+    utils::supported_mint,
+    PostedTokenBridgeRelayerMessage,
+};
 use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use anchor_spl::{
+    token::spl_token::native_mint,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use wormhole_anchor_sdk::{
+    token_bridge::{self, program::TokenBridge},
+    wormhole::program::Wormhole,
+};
+
+/// Returned by the inbound complete instructions.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CompleteTransferWithRelayResult {
+    /// Amount delivered to the recipient.
+    pub amount: u64,
+}
+
+/// Records the delivery in the receipt PDA, if one was provided.
+fn write_delivery_receipt(
+    delivery_receipt: &mut Option<Box<Account<DeliveryReceipt>>>,
+    bump: Option<u8>,
+    vaa: &PostedTokenBridgeRelayerMessage,
+    payer: &Pubkey,
+    recipient: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    if let (Some(delivery_receipt), Some(bump)) = (delivery_receipt.as_mut(), bump) {
+        delivery_receipt.set_inner(DeliveryReceipt {
+            bump,
+            emitter_chain: vaa.emitter_chain(),
+            emitter_address: *vaa.emitter_address(),
+            sequence: vaa.sequence(),
+            payer: *payer,
+            slot: Clock::get()?.slot,
+            recipient: *recipient,
+            amount,
+        });
+    }
+    Ok(())
+}
 
 pub struct RedeemToken<'ctx, 'info> {
     payer: &'ctx Signer<'info>,
@@ -56,3 +103,202 @@ pub fn redeem_token(redeem_token: RedeemToken, amount: u64) -> Result<()> {
         &[config_seeds],
     ))
 }
+
+/// Token Bridge accounts that differ between native and wrapped mints.
+enum TokenBridgeAccounts<'ctx, 'info> {
+    /// Native mints are released from the Token Bridge custody account.
+    Native {
+        custody: &'ctx UncheckedAccount<'info>,
+        custody_signer: &'ctx UncheckedAccount<'info>,
+    },
+    /// Token Bridge wrapped mints are minted.
+    Wrapped {
+        wrapped_meta: &'ctx UncheckedAccount<'info>,
+        mint_authority: &'ctx UncheckedAccount<'info>,
+    },
+}
+
+/// Accounts of the inbound complete instructions, which all share
+/// [`complete_with_relay`].
+struct CompleteWithRelay<'ctx, 'info> {
+    payer: &'ctx Signer<'info>,
+    config: &'ctx Account<'info, RedeemerConfig>,
+    mint: &'ctx InterfaceAccount<'info, Mint>,
+    recipient_token_account: &'ctx InterfaceAccount<'info, TokenAccount>,
+    recipient: &'ctx AccountInfo<'info>,
+    tmp_token_account: &'ctx InterfaceAccount<'info, TokenAccount>,
+    token_bridge_config: &'ctx UncheckedAccount<'info>,
+    vaa: &'ctx Account<'info, PostedTokenBridgeRelayerMessage>,
+    token_bridge_claim: &'ctx AccountInfo<'info>,
+    token_bridge_foreign_endpoint: &'ctx UncheckedAccount<'info>,
+    token_bridge: TokenBridgeAccounts<'ctx, 'info>,
+    delivery_receipt: &'ctx mut Option<Box<Account<'info, DeliveryReceipt>>>,
+    delivery_receipt_bump: Option<u8>,
+    wormhole_program: &'ctx Program<'info, Wormhole>,
+    token_bridge_program: &'ctx Program<'info, TokenBridge>,
+    token_program: &'ctx Interface<'info, TokenInterface>,
+    system_program: &'ctx Program<'info, System>,
+    rent: &'ctx UncheckedAccount<'info>,
+}
+
+/// Redeems the transfer with the Token Bridge and delivers the tokens (or, for
+/// wrapped SOL, the lamports) to the recipient. Returns the event for the
+/// caller to emit, since `emit_cpi!` needs the instruction's context.
+fn complete_with_relay(
+    accounts: CompleteWithRelay,
+) -> Result<(CompleteTransferWithRelayResult, TransferRedeemed)> {
+    let CompleteWithRelay {
+        payer,
+        config,
+        mint,
+        recipient_token_account,
+        recipient,
+        tmp_token_account,
+        token_bridge_config,
+        vaa,
+        token_bridge_claim,
+        token_bridge_foreign_endpoint,
+        token_bridge,
+        delivery_receipt,
+        delivery_receipt_bump,
+        wormhole_program,
+        token_bridge_program,
+        token_program,
+        system_program,
+        rent,
+    } = accounts;
+
+    // The intended recipient must agree with the recipient account.
+    let TokenBridgeRelayerMessage {
+        recipient: intended_recipient,
+    } = TokenBridgeRelayerMessage::decode(vaa.message().data().as_ref())?;
+    require!(
+        recipient.key() == Pubkey::from(intended_recipient),
+        TokenBridgeRelayerError::InvalidRecipient
+    );
+    require!(
+        supported_mint(&mint.to_account_info()),
+        TokenBridgeRelayerError::UnsupportedMint
+    );
+
+    // These seeds are used to:
+    // 1.  Redeem Token Bridge program's
+    //     complete_transfer_native_with_payload or
+    //     complete_transfer_wrapped_with_payload.
+    // 2.  Transfer tokens to recipient.
+    // 3.  Close tmp_token_account.
+    let config_seeds = &[RedeemerConfig::SEED_PREFIX.as_ref(), &[config.bump]];
+
+    // Redeem the token transfer to the tmp_token_account.
+    let (amount, unwrapped) = match token_bridge {
+        TokenBridgeAccounts::Native {
+            custody,
+            custody_signer,
+        } => {
+            crate::ext::complete_transfer_native_with_payload(CpiContext::new_with_signer(
+                token_bridge_program.to_account_info(),
+                crate::ext::CompleteTransferNativeWithPayload {
+                    payer: payer.to_account_info(),
+                    config: token_bridge_config.to_account_info(),
+                    vaa: vaa.to_account_info(),
+                    claim: token_bridge_claim.to_account_info(),
+                    foreign_endpoint: token_bridge_foreign_endpoint.to_account_info(),
+                    to: tmp_token_account.to_account_info(),
+                    redeemer: config.to_account_info(),
+                    custody: custody.to_account_info(),
+                    mint: mint.to_account_info(),
+                    custody_signer: custody_signer.to_account_info(),
+                    rent: rent.to_account_info(),
+                    system_program: system_program.to_account_info(),
+                    token_program: token_program.to_account_info(),
+                    wormhole_program: wormhole_program.to_account_info(),
+                },
+                &[config_seeds],
+            ))?;
+
+            // Denormalize the transfer amount encoded in
+            // the VAA.
+            let amount = token_bridge::denormalize_amount(vaa.data().amount(), mint.decimals);
+            (amount, mint.key() == native_mint::ID)
+        }
+        TokenBridgeAccounts::Wrapped {
+            wrapped_meta,
+            mint_authority,
+        } => {
+            crate::ext::complete_transfer_wrapped_with_payload(CpiContext::new_with_signer(
+                token_bridge_program.to_account_info(),
+                crate::ext::CompleteTransferWrappedWithPayload {
+                    payer: payer.to_account_info(),
+                    config: token_bridge_config.to_account_info(),
+                    vaa: vaa.to_account_info(),
+                    claim: token_bridge_claim.to_account_info(),
+                    foreign_endpoint: token_bridge_foreign_endpoint.to_account_info(),
+                    to: tmp_token_account.to_account_info(),
+                    redeemer: config.to_account_info(),
+                    wrapped_mint: mint.to_account_info(),
+                    wrapped_metadata: wrapped_meta.to_account_info(),
+                    mint_authority: mint_authority.to_account_info(),
+                    rent: rent.to_account_info(),
+                    system_program: system_program.to_account_info(),
+                    token_program: token_program.to_account_info(),
+                    wormhole_program: wormhole_program.to_account_info(),
+                },
+                &[config_seeds],
+            ))?;
+
+            // Wrapped mints never have more than 8 decimals, so the encoded
+            // amount does not need to be denormalized.
+            (vaa.data().amount(), false)
+        }
+    };
+
+    // Check to see if the transfer is for wrapped SOL. If it is,
+    // unwrap and transfer the SOL to the recipient.
+    if unwrapped {
+        // Transfer all lamports to the recipient.
+        anchor_spl::token_interface::close_account(CpiContext::new_with_signer(
+            token_program.to_account_info(),
+            anchor_spl::token_interface::CloseAccount {
+                account: tmp_token_account.to_account_info(),
+                destination: recipient.to_account_info(),
+                authority: config.to_account_info(),
+            },
+            &[config_seeds],
+        ))?;
+    } else {
+        redeem_token(
+            RedeemToken {
+                payer,
+                config,
+                mint,
+                recipient_token_account,
+                tmp_token_account,
+                token_program,
+            },
+            amount,
+        )?;
+    }
+
+    write_delivery_receipt(
+        delivery_receipt,
+        delivery_receipt_bump,
+        vaa,
+        &payer.key(),
+        &recipient.key(),
+        amount,
+    )?;
+
+    Ok((
+        CompleteTransferWithRelayResult { amount },
+        TransferRedeemed {
+            emitter_chain: vaa.emitter_chain(),
+            emitter_address: *vaa.emitter_address(),
+            sequence: vaa.sequence(),
+            mint: mint.key(),
+            amount,
+            recipient: recipient.key(),
+            unwrapped,
+            payer: payer.key(),
+        },
+    ))
+}
@@ -1,22 +1,24 @@
 use crate::{
     error::TokenBridgeRelayerError,
-    message::TokenBridgeRelayerMessage,
-    state::{RedeemerConfig, SEED_PREFIX_TMP},
+    state::{DeliveryReceipt, RedeemerConfig, SEED_PREFIX_TMP},
+    utils::{token_bridge_pda, TOKEN_BRIDGE_CONFIG, TOKEN_BRIDGE_CUSTODY_SIGNER},
     PostedTokenBridgeRelayerMessage, OUR_CHAIN,
 };
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::sysvar::rent};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::spl_token::native_mint,
     token_interface::{Mint, TokenAccount, TokenInterface},
 };
 use wormhole_anchor_sdk::{
-    token_bridge::{self, program::TokenBridge},
+    token_bridge::program::TokenBridge,
     wormhole::{program::Wormhole, SEED_PREFIX_POSTED_VAA},
 };
 
-use super::{redeem_token, RedeemToken};
+use super::{
+    complete_with_relay, CompleteTransferWithRelayResult, CompleteWithRelay, TokenBridgeAccounts,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(vaa_hash: [u8; 32])]
 pub struct CompleteNativeWithRelay<'info> {
@@ -61,7 +63,7 @@ pub struct CompleteNativeWithRelay<'info> {
         payer = payer,
         seeds = [
             SEED_PREFIX_TMP,
-            mint.key().as_ref(),
+            &vaa_hash,
         ],
         bump,
         token::mint = mint,
@@ -72,9 +74,15 @@ pub struct CompleteNativeWithRelay<'info> {
     /// instruction is invoked to temporarily take custody of the payer's
     /// tokens. When the tokens are finally bridged in, the tokens will be
     /// transferred to the destination token accounts. This account will have
-    /// zero balance and can be closed.
+    /// zero balance and can be closed. It is seeded by the VAA hash, which is
+    /// unique per transfer, so that transfers of the same mint never contend
+    /// for it.
     pub tmp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        address = TOKEN_BRIDGE_CONFIG
+            @ TokenBridgeRelayerError::InvalidTokenBridgeConfig
+    )]
     /// CHECK: Token Bridge config. Read-only.
     pub token_bridge_config: UncheckedAccount<'info>,
 
@@ -95,6 +103,11 @@ pub struct CompleteNativeWithRelay<'info> {
 
     #[account(
         mut,
+        address = token_bridge_pda(&[
+            vaa.emitter_address(),
+            &vaa.emitter_chain().to_be_bytes(),
+            &vaa.sequence().to_be_bytes(),
+        ]) @ TokenBridgeRelayerError::InvalidTokenBridgeClaim,
         constraint = token_bridge_claim.data_is_empty() @ TokenBridgeRelayerError::AlreadyRedeemed
     )]
     /// CHECK: Token Bridge claim account. It stores a boolean, whose value
@@ -110,6 +123,12 @@ pub struct CompleteNativeWithRelay<'info> {
     /// first evaluation of this instruction.
     pub token_bridge_claim: AccountInfo<'info>,
 
+    #[account(
+        address = token_bridge_pda(&[
+            &vaa.emitter_chain().to_be_bytes(),
+            vaa.emitter_address(),
+        ]) @ TokenBridgeRelayerError::InvalidTokenBridgeForeignEndpoint
+    )]
     /// CHECK: Token Bridge foreign endpoint. This account should really be one
     /// endpoint per chain, but the PDA allows for multiple endpoints for each
     /// chain! We store the proper endpoint for the emitter chain.
@@ -117,18 +136,42 @@ pub struct CompleteNativeWithRelay<'info> {
 
     /// CHECK: Token Bridge custody. This is the Token Bridge program's token
     /// account that holds this mint's balance.
-    #[account(mut)]
+    #[account(
+        mut,
+        address = token_bridge_pda(&[mint.key().as_ref()])
+            @ TokenBridgeRelayerError::InvalidTokenBridgeCustody
+    )]
     pub token_bridge_custody: UncheckedAccount<'info>,
 
+    #[account(
+        address = TOKEN_BRIDGE_CUSTODY_SIGNER
+            @ TokenBridgeRelayerError::InvalidTokenBridgeCustodySigner
+    )]
     /// CHECK: Token Bridge custody signer. Read-only.
     pub token_bridge_custody_signer: UncheckedAccount<'info>,
 
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DeliveryReceipt::INIT_SPACE,
+        seeds = [
+            DeliveryReceipt::SEED_PREFIX,
+            &vaa.emitter_chain().to_be_bytes(),
+            vaa.emitter_address().as_ref(),
+            &vaa.sequence().to_be_bytes(),
+        ],
+        bump,
+    )]
+    /// Optional delivery receipt for this VAA, paid for by the payer.
+    pub delivery_receipt: Option<Box<Account<'info, DeliveryReceipt>>>,
+
     pub wormhole_program: Program<'info, Wormhole>,
     pub token_bridge_program: Program<'info, TokenBridge>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 
+    #[account(address = rent::ID)]
     /// CHECK: Token Bridge program needs rent sysvar.
     pub rent: UncheckedAccount<'info>,
 }
@@ -136,81 +179,32 @@ pub struct CompleteNativeWithRelay<'info> {
 pub fn complete_native_transfer_with_relay(
     ctx: Context<CompleteNativeWithRelay>,
     _vaa_hash: [u8; 32],
-) -> Result<()> {
-    // The intended recipient must agree with the recipient account.
-    let TokenBridgeRelayerMessage { recipient } = *ctx.accounts.vaa.message().data();
-    require!(
-        ctx.accounts.recipient.key() == Pubkey::from(recipient),
-        TokenBridgeRelayerError::InvalidRecipient
-    );
-
-    // These seeds are used to:
-    // 1.  Redeem Token Bridge program's
-    //     complete_transfer_native_with_payload.
-    // 2.  Transfer tokens to recipient.
-    // 3.  Close tmp_token_account.
-    let config_seeds = &[
-        RedeemerConfig::SEED_PREFIX.as_ref(),
-        &[ctx.accounts.config.bump],
-    ];
-
-    // Redeem the token transfer to the tmp_token_account.
-    crate::ext::complete_transfer_native_with_payload(CpiContext::new_with_signer(
-        ctx.accounts.token_bridge_program.to_account_info(),
-        crate::ext::CompleteTransferNativeWithPayload {
-            payer: ctx.accounts.payer.to_account_info(),
-            config: ctx.accounts.token_bridge_config.to_account_info(),
-            vaa: ctx.accounts.vaa.to_account_info(),
-            claim: ctx.accounts.token_bridge_claim.to_account_info(),
-            foreign_endpoint: ctx.accounts.token_bridge_foreign_endpoint.to_account_info(),
-            to: ctx.accounts.tmp_token_account.to_account_info(),
-            redeemer: ctx.accounts.config.to_account_info(),
-            custody: ctx.accounts.token_bridge_custody.to_account_info(),
-            mint: ctx.accounts.mint.to_account_info(),
-            custody_signer: ctx.accounts.token_bridge_custody_signer.to_account_info(),
-            rent: ctx.accounts.rent.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-            token_program: ctx.accounts.token_program.to_account_info(),
-            wormhole_program: ctx.accounts.wormhole_program.to_account_info(),
+) -> Result<CompleteTransferWithRelayResult> {
+    let (result, event) = complete_with_relay(CompleteWithRelay {
+        payer: &ctx.accounts.payer,
+        config: &ctx.accounts.config,
+        mint: &ctx.accounts.mint,
+        recipient_token_account: &ctx.accounts.recipient_token_account,
+        recipient: &ctx.accounts.recipient,
+        tmp_token_account: &ctx.accounts.tmp_token_account,
+        token_bridge_config: &ctx.accounts.token_bridge_config,
+        vaa: &ctx.accounts.vaa,
+        token_bridge_claim: &ctx.accounts.token_bridge_claim,
+        token_bridge_foreign_endpoint: &ctx.accounts.token_bridge_foreign_endpoint,
+        token_bridge: TokenBridgeAccounts::Native {
+            custody: &ctx.accounts.token_bridge_custody,
+            custody_signer: &ctx.accounts.token_bridge_custody_signer,
         },
-        &[config_seeds],
-    ))?;
-
-    // Denormalize the transfer amount encoded in
-    // the VAA.
-    let amount = token_bridge::denormalize_amount(
-        ctx.accounts.vaa.data().amount(),
-        ctx.accounts.mint.decimals,
-    );
-
-    // Check to see if the transfer is for wrapped SOL. If it is,
-    // unwrap and transfer the SOL to the recipient and relayer.
-    // Since we are unwrapping the SOL, this contract will not
-    // perform a swap with the off-chain relayer.
-    if ctx.accounts.mint.key() == native_mint::ID {
-        // Transfer all lamports to the recipient.
-        anchor_spl::token_interface::close_account(CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            anchor_spl::token_interface::CloseAccount {
-                account: ctx.accounts.tmp_token_account.to_account_info(),
-                destination: ctx.accounts.recipient.to_account_info(),
-                authority: ctx.accounts.config.to_account_info(),
-            },
-            &[config_seeds],
-        ))?;
-
-        Ok(())
-    } else {
-        redeem_token(
-            RedeemToken {
-                payer: &ctx.accounts.payer,
-                config: &ctx.accounts.config,
-                mint: &ctx.accounts.mint,
-                recipient_token_account: &ctx.accounts.recipient_token_account,
-                tmp_token_account: &ctx.accounts.tmp_token_account,
-                token_program: &ctx.accounts.token_program,
-            },
-            amount,
-        )
-    }
+        delivery_receipt: &mut ctx.accounts.delivery_receipt,
+        delivery_receipt_bump: ctx.bumps.delivery_receipt,
+        wormhole_program: &ctx.accounts.wormhole_program,
+        token_bridge_program: &ctx.accounts.token_bridge_program,
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+        rent: &ctx.accounts.rent,
+    })?;
+
+    emit_cpi!(event);
+
+    Ok(result)
 }
@@ -0,0 +1,255 @@
+use crate::{
+    error::TokenBridgeRelayerError,
+    state::{DeliveryReceipt, RedeemerConfig, SEED_PREFIX_TMP},
+    utils::{
+        token_bridge_pda, TOKEN_BRIDGE_CONFIG, TOKEN_BRIDGE_CUSTODY_SIGNER,
+        TOKEN_BRIDGE_MINT_AUTHORITY,
+    },
+    PostedTokenBridgeRelayerMessage, OUR_CHAIN,
+};
+use anchor_lang::{prelude::*, solana_program::sysvar::rent};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+use wormhole_anchor_sdk::{
+    token_bridge::program::TokenBridge,
+    wormhole::{program::Wormhole, SEED_PREFIX_POSTED_VAA},
+};
+
+use super::{
+    complete_with_relay, CompleteTransferWithRelayResult, CompleteWithRelay, TokenBridgeAccounts,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+#[instruction(vaa_hash: [u8; 32])]
+pub struct CompleteTransferWithRelay<'info> {
+    #[account(mut)]
+    /// Payer will pay Wormhole fee to transfer tokens and create temporary
+    /// token account.
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [RedeemerConfig::SEED_PREFIX],
+        bump = config.bump
+    )]
+    /// Redeemer Config account. Acts as the Token Bridge redeemer, which signs
+    /// for the complete transfer instruction. Read-only.
+    pub config: Box<Account<'info, RedeemerConfig>>,
+
+    #[account(mut)]
+    /// Mint info. This is the SPL token that will be bridged over from the
+    /// foreign contract. This must match the token address specified in the
+    /// signed Wormhole message, or the Token Bridge wrapped mint for it.
+    /// Mutable, since wrapped tokens are minted.
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = recipient,
+        associated_token::token_program = token_program
+    )]
+    /// Recipient associated token account. The recipient authority check
+    /// is necessary to ensure that the recipient is the intended recipient
+    /// of the bridged tokens and create the token account if needed. Mutable.
+    pub recipient_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut)]
+    /// CHECK: recipient may differ from payer if a relayer paid for this
+    /// transaction. This instruction verifies that the recipient key
+    /// passed in this context matches the intended recipient in the vaa.
+    pub recipient: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            SEED_PREFIX_TMP,
+            &vaa_hash,
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = config,
+        token::token_program = token_program
+    )]
+    /// Program's temporary token account. This account is created before the
+    /// instruction is invoked to temporarily take custody of the payer's
+    /// tokens. When the tokens are finally bridged in, the tokens will be
+    /// transferred to the destination token accounts. This account will have
+    /// zero balance and can be closed. It is seeded by the VAA hash, which is
+    /// unique per transfer, so that transfers of the same mint never contend
+    /// for it.
+    pub tmp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = TOKEN_BRIDGE_CONFIG
+            @ TokenBridgeRelayerError::InvalidTokenBridgeConfig
+    )]
+    /// CHECK: Token Bridge config. Read-only.
+    pub token_bridge_config: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [
+            SEED_PREFIX_POSTED_VAA,
+            &vaa_hash
+        ],
+        bump,
+        seeds::program = wormhole_program.key(),
+        constraint = vaa.data().to() == crate::ID @ TokenBridgeRelayerError::InvalidTransferToAddress,
+        constraint = vaa.data().to_chain() == OUR_CHAIN @ TokenBridgeRelayerError::InvalidTransferToChain
+    )]
+    /// Verified Wormhole message account. The Wormhole program verified
+    /// signatures and posted the account data here. Read-only.
+    pub vaa: Box<Account<'info, PostedTokenBridgeRelayerMessage>>,
+
+    #[account(
+        mut,
+        address = token_bridge_pda(&[
+            vaa.emitter_address(),
+            &vaa.emitter_chain().to_be_bytes(),
+            &vaa.sequence().to_be_bytes(),
+        ]) @ TokenBridgeRelayerError::InvalidTokenBridgeClaim,
+        constraint = token_bridge_claim.data_is_empty() @ TokenBridgeRelayerError::AlreadyRedeemed
+    )]
+    /// CHECK: Token Bridge claim account. It stores a boolean, whose value
+    /// is true if the bridged assets have been claimed. If the transfer has
+    /// not been redeemed, this account will not exist yet.
+    ///
+    /// NOTE: The Token Bridge program's claim account is only initialized when
+    /// a transfer is redeemed (and the boolean value `true` is written as
+    /// its data).
+    ///
+    /// The Token Bridge program will automatically fail if this transfer
+    /// is redeemed again. But we choose to short-circuit the failure as the
+    /// first evaluation of this instruction.
+    pub token_bridge_claim: AccountInfo<'info>,
+
+    #[account(
+        address = token_bridge_pda(&[
+            &vaa.emitter_chain().to_be_bytes(),
+            vaa.emitter_address(),
+        ]) @ TokenBridgeRelayerError::InvalidTokenBridgeForeignEndpoint
+    )]
+    /// CHECK: Token Bridge foreign endpoint. This account should really be one
+    /// endpoint per chain, but the PDA allows for multiple endpoints for each
+    /// chain! We store the proper endpoint for the emitter chain.
+    pub token_bridge_foreign_endpoint: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = token_bridge_pda(&[mint.key().as_ref()])
+            @ TokenBridgeRelayerError::InvalidTokenBridgeCustody
+    )]
+    /// CHECK: Token Bridge custody. This is the Token Bridge program's token
+    /// account that holds this mint's balance. Only required for native
+    /// mints. Mutable.
+    pub token_bridge_custody: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        address = TOKEN_BRIDGE_CUSTODY_SIGNER
+            @ TokenBridgeRelayerError::InvalidTokenBridgeCustodySigner
+    )]
+    /// CHECK: Token Bridge custody signer. Only required for native mints.
+    /// Read-only.
+    pub token_bridge_custody_signer: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        address = token_bridge_pda(&[b"meta", mint.key().as_ref()])
+            @ TokenBridgeRelayerError::InvalidTokenBridgeWrappedMeta
+    )]
+    /// CHECK: Token Bridge program's wrapped metadata. Only required for
+    /// wrapped mints. Read-only.
+    pub token_bridge_wrapped_meta: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        address = TOKEN_BRIDGE_MINT_AUTHORITY
+            @ TokenBridgeRelayerError::InvalidTokenBridgeMintAuthority
+    )]
+    /// CHECK: Token Bridge mint authority. Only required for wrapped mints.
+    /// Read-only.
+    pub token_bridge_mint_authority: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DeliveryReceipt::INIT_SPACE,
+        seeds = [
+            DeliveryReceipt::SEED_PREFIX,
+            &vaa.emitter_chain().to_be_bytes(),
+            vaa.emitter_address().as_ref(),
+            &vaa.sequence().to_be_bytes(),
+        ],
+        bump,
+    )]
+    /// Optional delivery receipt for this VAA, paid for by the payer.
+    pub delivery_receipt: Option<Box<Account<'info, DeliveryReceipt>>>,
+
+    pub wormhole_program: Program<'info, Wormhole>,
+    pub token_bridge_program: Program<'info, TokenBridge>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+
+    #[account(address = rent::ID)]
+    /// CHECK: Token Bridge program needs rent sysvar.
+    pub rent: UncheckedAccount<'info>,
+}
+
+pub fn complete_transfer_with_relay(
+    ctx: Context<CompleteTransferWithRelay>,
+    _vaa_hash: [u8; 32],
+) -> Result<CompleteTransferWithRelayResult> {
+    // Tokens originating from this chain are held in custody by the Token
+    // Bridge, everything else is a Token Bridge wrapped mint.
+    let token_bridge = if ctx.accounts.vaa.data().token_chain() == OUR_CHAIN {
+        let (custody, custody_signer) = ctx
+            .accounts
+            .token_bridge_custody
+            .as_ref()
+            .zip(ctx.accounts.token_bridge_custody_signer.as_ref())
+            .ok_or(TokenBridgeRelayerError::TokenBridgeCustodyRequired)?;
+        TokenBridgeAccounts::Native {
+            custody,
+            custody_signer,
+        }
+    } else {
+        let (wrapped_meta, mint_authority) = ctx
+            .accounts
+            .token_bridge_wrapped_meta
+            .as_ref()
+            .zip(ctx.accounts.token_bridge_mint_authority.as_ref())
+            .ok_or(TokenBridgeRelayerError::TokenBridgeWrappedMetaRequired)?;
+        TokenBridgeAccounts::Wrapped {
+            wrapped_meta,
+            mint_authority,
+        }
+    };
+
+    let (result, event) = complete_with_relay(CompleteWithRelay {
+        payer: &ctx.accounts.payer,
+        config: &ctx.accounts.config,
+        mint: &ctx.accounts.mint,
+        recipient_token_account: &ctx.accounts.recipient_token_account,
+        recipient: &ctx.accounts.recipient,
+        tmp_token_account: &ctx.accounts.tmp_token_account,
+        token_bridge_config: &ctx.accounts.token_bridge_config,
+        vaa: &ctx.accounts.vaa,
+        token_bridge_claim: &ctx.accounts.token_bridge_claim,
+        token_bridge_foreign_endpoint: &ctx.accounts.token_bridge_foreign_endpoint,
+        token_bridge,
+        delivery_receipt: &mut ctx.accounts.delivery_receipt,
+        delivery_receipt_bump: ctx.bumps.delivery_receipt,
+        wormhole_program: &ctx.accounts.wormhole_program,
+        token_bridge_program: &ctx.accounts.token_bridge_program,
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+        rent: &ctx.accounts.rent,
+    })?;
+
+    emit_cpi!(event);
+
+    Ok(result)
+}
@@ -1,10 +1,10 @@
 use crate::{
     error::TokenBridgeRelayerError,
-    message::TokenBridgeRelayerMessage,
-    state::{RedeemerConfig, SEED_PREFIX_TMP},
+    state::{DeliveryReceipt, RedeemerConfig, SEED_PREFIX_TMP},
+    utils::{token_bridge_pda, TOKEN_BRIDGE_CONFIG, TOKEN_BRIDGE_MINT_AUTHORITY},
     PostedTokenBridgeRelayerMessage, OUR_CHAIN,
 };
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, solana_program::sysvar::rent};
 use anchor_spl::{
     associated_token::AssociatedToken,
     token_interface::{Mint, TokenAccount, TokenInterface},
@@ -14,8 +14,11 @@ use wormhole_anchor_sdk::{
     wormhole::{program::Wormhole, SEED_PREFIX_POSTED_VAA},
 };
 
-use super::{redeem_token, RedeemToken};
+use super::{
+    complete_with_relay, CompleteTransferWithRelayResult, CompleteWithRelay, TokenBridgeAccounts,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
 #[instruction(vaa_hash: [u8; 32])]
 pub struct CompleteWrappedWithRelay<'info> {
@@ -61,7 +64,7 @@ pub struct CompleteWrappedWithRelay<'info> {
         payer = payer,
         seeds = [
             SEED_PREFIX_TMP,
-            token_bridge_wrapped_mint.key().as_ref(),
+            &vaa_hash,
         ],
         bump,
         token::mint = token_bridge_wrapped_mint,
@@ -72,9 +75,15 @@ pub struct CompleteWrappedWithRelay<'info> {
     /// instruction is invoked to temporarily take custody of the payer's
     /// tokens. When the tokens are finally bridged in, the tokens will be
     /// transferred to the destination token accounts. This account will have
-    /// zero balance and can be closed.
+    /// zero balance and can be closed. It is seeded by the VAA hash, which is
+    /// unique per transfer, so that transfers of the same mint never contend
+    /// for it.
     pub tmp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        address = token_bridge_pda(&[b"meta", token_bridge_wrapped_mint.key().as_ref()])
+            @ TokenBridgeRelayerError::InvalidTokenBridgeWrappedMeta
+    )]
     /// CHECK: Token Bridge program's wrapped metadata, which stores info
     /// about the token from its native chain:
     ///   * Wormhole Chain ID
@@ -82,6 +91,10 @@ pub struct CompleteWrappedWithRelay<'info> {
     ///   * Token's native decimals
     pub token_bridge_wrapped_meta: UncheckedAccount<'info>,
 
+    #[account(
+        address = TOKEN_BRIDGE_CONFIG
+            @ TokenBridgeRelayerError::InvalidTokenBridgeConfig
+    )]
     /// CHECK: Token Bridge config. Read-only.
     pub token_bridge_config: UncheckedAccount<'info>,
 
@@ -102,6 +115,11 @@ pub struct CompleteWrappedWithRelay<'info> {
 
     #[account(
         mut,
+        address = token_bridge_pda(&[
+            vaa.emitter_address(),
+            &vaa.emitter_chain().to_be_bytes(),
+            &vaa.sequence().to_be_bytes(),
+        ]) @ TokenBridgeRelayerError::InvalidTokenBridgeClaim,
         constraint = token_bridge_claim.data_is_empty() @ TokenBridgeRelayerError::AlreadyRedeemed
     )]
     /// CHECK: Token Bridge claim account. It stores a boolean, whose value
@@ -117,20 +135,46 @@ pub struct CompleteWrappedWithRelay<'info> {
     /// first evaluation of this instruction.
     pub token_bridge_claim: AccountInfo<'info>,
 
+    #[account(
+        address = token_bridge_pda(&[
+            &vaa.emitter_chain().to_be_bytes(),
+            vaa.emitter_address(),
+        ]) @ TokenBridgeRelayerError::InvalidTokenBridgeForeignEndpoint
+    )]
     /// CHECK: Token Bridge foreign endpoint. This account should really be one
     /// endpoint per chain, but the PDA allows for multiple endpoints for each
     /// chain! We store the proper endpoint for the emitter chain.
     pub token_bridge_foreign_endpoint: UncheckedAccount<'info>,
 
-    /// CHECK: Token Bridge custody signer. Read-only.
+    #[account(
+        address = TOKEN_BRIDGE_MINT_AUTHORITY
+            @ TokenBridgeRelayerError::InvalidTokenBridgeMintAuthority
+    )]
+    /// CHECK: Token Bridge mint authority. Read-only.
     pub token_bridge_mint_authority: UncheckedAccount<'info>,
 
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + DeliveryReceipt::INIT_SPACE,
+        seeds = [
+            DeliveryReceipt::SEED_PREFIX,
+            &vaa.emitter_chain().to_be_bytes(),
+            vaa.emitter_address().as_ref(),
+            &vaa.sequence().to_be_bytes(),
+        ],
+        bump,
+    )]
+    /// Optional delivery receipt for this VAA, paid for by the payer.
+    pub delivery_receipt: Option<Box<Account<'info, DeliveryReceipt>>>,
+
     pub wormhole_program: Program<'info, Wormhole>,
     pub token_bridge_program: Program<'info, TokenBridge>,
     pub token_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 
+    #[account(address = rent::ID)]
     /// CHECK: Token Bridge program needs rent sysvar.
     pub rent: UncheckedAccount<'info>,
 }
@@ -138,48 +182,32 @@ pub struct CompleteWrappedWithRelay<'info> {
 pub fn complete_wrapped_transfer_with_relay(
     ctx: Context<CompleteWrappedWithRelay>,
     _vaa_hash: [u8; 32],
-) -> Result<()> {
-    // The intended recipient must agree with the recipient account.
-    let TokenBridgeRelayerMessage { recipient } = *ctx.accounts.vaa.message().data();
-    require!(
-        ctx.accounts.recipient.key() == Pubkey::from(recipient),
-        TokenBridgeRelayerError::InvalidRecipient
-    );
-
-    // Redeem the token transfer to the tmp_token_account.
-    crate::ext::complete_transfer_wrapped_with_payload(CpiContext::new_with_signer(
-        ctx.accounts.token_bridge_program.to_account_info(),
-        crate::ext::CompleteTransferWrappedWithPayload {
-            payer: ctx.accounts.payer.to_account_info(),
-            config: ctx.accounts.token_bridge_config.to_account_info(),
-            vaa: ctx.accounts.vaa.to_account_info(),
-            claim: ctx.accounts.token_bridge_claim.to_account_info(),
-            foreign_endpoint: ctx.accounts.token_bridge_foreign_endpoint.to_account_info(),
-            to: ctx.accounts.tmp_token_account.to_account_info(),
-            redeemer: ctx.accounts.config.to_account_info(),
-            wrapped_mint: ctx.accounts.token_bridge_wrapped_mint.to_account_info(),
-            wrapped_metadata: ctx.accounts.token_bridge_wrapped_meta.to_account_info(),
-            mint_authority: ctx.accounts.token_bridge_mint_authority.to_account_info(),
-            rent: ctx.accounts.rent.to_account_info(),
-            system_program: ctx.accounts.system_program.to_account_info(),
-            token_program: ctx.accounts.token_program.to_account_info(),
-            wormhole_program: ctx.accounts.wormhole_program.to_account_info(),
-        },
-        &[&[
-            RedeemerConfig::SEED_PREFIX.as_ref(),
-            &[ctx.accounts.config.bump],
-        ]],
-    ))?;
-
-    redeem_token(
-        RedeemToken {
-            payer: &ctx.accounts.payer,
-            config: &ctx.accounts.config,
-            mint: &ctx.accounts.token_bridge_wrapped_mint,
-            recipient_token_account: &ctx.accounts.recipient_token_account,
-            tmp_token_account: &ctx.accounts.tmp_token_account,
-            token_program: &ctx.accounts.token_program,
+) -> Result<CompleteTransferWithRelayResult> {
+    let (result, event) = complete_with_relay(CompleteWithRelay {
+        payer: &ctx.accounts.payer,
+        config: &ctx.accounts.config,
+        mint: &ctx.accounts.token_bridge_wrapped_mint,
+        recipient_token_account: &ctx.accounts.recipient_token_account,
+        recipient: &ctx.accounts.recipient,
+        tmp_token_account: &ctx.accounts.tmp_token_account,
+        token_bridge_config: &ctx.accounts.token_bridge_config,
+        vaa: &ctx.accounts.vaa,
+        token_bridge_claim: &ctx.accounts.token_bridge_claim,
+        token_bridge_foreign_endpoint: &ctx.accounts.token_bridge_foreign_endpoint,
+        token_bridge: TokenBridgeAccounts::Wrapped {
+            wrapped_meta: &ctx.accounts.token_bridge_wrapped_meta,
+            mint_authority: &ctx.accounts.token_bridge_mint_authority,
         },
-        ctx.accounts.vaa.data().amount(),
-    )
+        delivery_receipt: &mut ctx.accounts.delivery_receipt,
+        delivery_receipt_bump: ctx.bumps.delivery_receipt,
+        wormhole_program: &ctx.accounts.wormhole_program,
+        token_bridge_program: &ctx.accounts.token_bridge_program,
+        token_program: &ctx.accounts.token_program,
+        system_program: &ctx.accounts.system_program,
+        rent: &ctx.accounts.rent,
+    })?;
+
+    emit_cpi!(event);
+
+    Ok(result)
 }
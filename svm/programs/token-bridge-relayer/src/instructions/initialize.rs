@@ -1,5 +1,3 @@
-declare_program!(executor);
-
 use anchor_lang::{
     prelude::*,
     solana_program::{
@@ -9,18 +7,18 @@ use anchor_lang::{
     },
 };
 use anchor_spl::{associated_token::AssociatedToken, token::Token, token_2022::Token2022};
-use wormhole_anchor_sdk::{
-    token_bridge::{
-        program::TokenBridge, Config, SEED_PREFIX_AUTHORITY_SIGNER, SEED_PREFIX_CUSTODY_SIGNER,
-        SEED_PREFIX_EMITTER, SEED_PREFIX_MINT_AUTHORITY,
+use wormhole_anchor_sdk::{token_bridge::program::TokenBridge, wormhole::program::Wormhole};
+
+use crate::{
+    state::{RedeemerConfig, SenderConfig, LUT, SEED_LUT_AUTHORITY, SEED_PREFIX_LUT},
+    utils::{
+        TOKEN_BRIDGE_AUTHORITY_SIGNER, TOKEN_BRIDGE_CONFIG, TOKEN_BRIDGE_CUSTODY_SIGNER,
+        TOKEN_BRIDGE_EMITTER, TOKEN_BRIDGE_MINT_AUTHORITY, TOKEN_BRIDGE_SEQUENCE, WORMHOLE_BRIDGE,
+        WORMHOLE_FEE_COLLECTOR,
     },
-    wormhole::{program::Wormhole, BridgeData, FeeCollector, SequenceTracker},
+    EXECUTOR_ID,
 };
 
-use crate::state::{RedeemerConfig, SenderConfig, LUT, SEED_LUT_AUTHORITY, SEED_PREFIX_LUT};
-
-use executor::program::Executor;
-
 #[derive(Accounts)]
 #[instruction(recent_slot: u64)]
 pub struct Initialize<'info> {
@@ -125,33 +123,27 @@ pub fn initialize(ctx: Context<Initialize>, recent_slot: u64) -> Result<()> {
         ],
     )?;
 
-    let emitter = Pubkey::find_program_address(&[SEED_PREFIX_EMITTER], &TokenBridge::id()).0;
-
     let entries = vec![
         crate::id(),
         Pubkey::find_program_address(&[SenderConfig::SEED_PREFIX], &crate::id()).0,
         Pubkey::find_program_address(&[RedeemerConfig::SEED_PREFIX], &crate::id()).0,
         TokenBridge::id(),
-        Pubkey::find_program_address(&[Config::SEED_PREFIX], &TokenBridge::id()).0,
-        Pubkey::find_program_address(&[SEED_PREFIX_AUTHORITY_SIGNER], &TokenBridge::id()).0,
-        Pubkey::find_program_address(&[SEED_PREFIX_CUSTODY_SIGNER], &TokenBridge::id()).0,
-        Pubkey::find_program_address(&[SEED_PREFIX_MINT_AUTHORITY], &TokenBridge::id()).0,
-        emitter,
-        Pubkey::find_program_address(
-            &[SequenceTracker::SEED_PREFIX, emitter.as_ref()],
-            &Wormhole::id(),
-        )
-        .0,
+        TOKEN_BRIDGE_CONFIG,
+        TOKEN_BRIDGE_AUTHORITY_SIGNER,
+        TOKEN_BRIDGE_CUSTODY_SIGNER,
+        TOKEN_BRIDGE_MINT_AUTHORITY,
+        TOKEN_BRIDGE_EMITTER,
+        TOKEN_BRIDGE_SEQUENCE,
         Wormhole::id(),
-        Pubkey::find_program_address(&[BridgeData::SEED_PREFIX], &Wormhole::id()).0,
-        Pubkey::find_program_address(&[FeeCollector::SEED_PREFIX], &Wormhole::id()).0,
+        WORMHOLE_BRIDGE,
+        WORMHOLE_FEE_COLLECTOR,
         Token::id(),
         Token2022::id(),
         AssociatedToken::id(),
         System::id(),
         clock::id(),
         Rent::id(),
-        Executor::id(),
+        EXECUTOR_ID,
     ];
 
     let ix = address_lookup_table::instruction::extend_lookup_table(
@@ -1,11 +1,28 @@
+anchor_lang::declare_program!(executor);
+
 mod resolve_execute_vaa_v1;
 pub use resolve_execute_vaa_v1::*;
 
+mod resolve_transfer;
+pub use resolve_transfer::*;
+
+mod preview_transfer;
+pub use preview_transfer::*;
+
 mod initialize;
 pub use initialize::*;
 
+mod close_transfer_receipt;
+pub use close_transfer_receipt::*;
+
+mod close_delivery_receipt;
+pub use close_delivery_receipt::*;
+
 mod complete_transfer_with_relay;
 pub use complete_transfer_with_relay::*;
 
 mod transfer_tokens_with_relay;
 pub use transfer_tokens_with_relay::*;
+
+mod request_execution_for_sequence;
+pub use request_execution_for_sequence::*;
@@ -0,0 +1,119 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{token::TokenAccount, token_interface::Mint};
+use wormhole_anchor_sdk::{token_bridge, wormhole};
+
+use crate::{
+    error::TokenBridgeRelayerError,
+    ext::make_vaa_v1_request,
+    message::TokenBridgeRelayerMessage,
+    utils::{
+        supported_mint, valid_foreign_address, TOKEN_BRIDGE_EMITTER, TOKEN_BRIDGE_SEQUENCE,
+        WORMHOLE_BRIDGE,
+    },
+    OUR_CHAIN,
+};
+
+/// Size of a Core Bridge posted message without its payload.
+const POSTED_MESSAGE_BASE_LEN: usize = 95;
+
+/// Size of a Token Bridge transfer with payload without the payload.
+const TRANSFER_WITH_PAYLOAD_BASE_LEN: usize = 133;
+
+#[derive(Accounts)]
+pub struct PreviewTransfer<'info> {
+    /// Mint that would be transferred.
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        address = WORMHOLE_BRIDGE
+            @ TokenBridgeRelayerError::InvalidWormholeBridge
+    )]
+    /// Wormhole bridge data, which holds the message fee.
+    pub wormhole_bridge: Box<Account<'info, wormhole::BridgeData>>,
+
+    #[account(
+        address = TOKEN_BRIDGE_SEQUENCE @ TokenBridgeRelayerError::InvalidTokenBridgeSequence
+    )]
+    /// CHECK: Token Bridge sequence. May not exist before the first transfer.
+    pub token_bridge_sequence: UncheckedAccount<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PreviewTransferArgs {
+    pub amount: u64,
+    pub recipient_chain: u16,
+    pub recipient_address: [u8; 32],
+}
+
+/// Returned by `preview_transfer`. Everything a transfer of the same amount
+/// would do, as of the current state.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct PreviewTransferResult {
+    /// Amount bridged after the Token Bridge 8 decimal truncation. A transfer
+    /// fails with `ZeroBridgeAmount` if this is zero.
+    pub bridged_amount: u64,
+    /// Amount left with the sender because of the truncation.
+    pub dust: u64,
+    /// Wormhole message fee paid by the payer.
+    pub wormhole_fee: u64,
+    /// Rent for the temporary token account, returned to the payer at the
+    /// end of the transfer.
+    pub tmp_account_rent: u64,
+    /// Rent for the Wormhole message account, which cannot be reclaimed.
+    pub message_rent: u64,
+    /// Token Bridge payload (`TokenBridgeRelayerMessage`).
+    pub payload: Vec<u8>,
+    /// Sequence the transfer would be emitted with.
+    pub sequence: u64,
+    /// Executor request for that sequence.
+    pub request_bytes: Vec<u8>,
+}
+
+pub fn preview_transfer(
+    ctx: Context<PreviewTransfer>,
+    args: PreviewTransferArgs,
+) -> Result<PreviewTransferResult> {
+    let PreviewTransferArgs {
+        amount,
+        recipient_chain,
+        recipient_address,
+    } = args;
+
+    require!(
+        valid_foreign_address(recipient_chain, &recipient_address),
+        TokenBridgeRelayerError::InvalidRecipient,
+    );
+    require!(
+        supported_mint(&ctx.accounts.mint.to_account_info()),
+        TokenBridgeRelayerError::UnsupportedMint
+    );
+
+    let bridged_amount = token_bridge::truncate_amount(amount, ctx.accounts.mint.decimals);
+    let payload = TokenBridgeRelayerMessage {
+        recipient: recipient_address,
+    }
+    .encode();
+
+    let rent = Rent::get()?;
+
+    // The sequence account is created with the first message.
+    let sequence = if ctx.accounts.token_bridge_sequence.data_is_empty() {
+        0
+    } else {
+        let mut buf = &ctx.accounts.token_bridge_sequence.try_borrow_data()?[..];
+        wormhole::SequenceTracker::try_deserialize(&mut buf)?.sequence
+    };
+
+    Ok(PreviewTransferResult {
+        bridged_amount,
+        dust: amount - bridged_amount,
+        wormhole_fee: ctx.accounts.wormhole_bridge.fee(),
+        tmp_account_rent: rent.minimum_balance(TokenAccount::LEN),
+        message_rent: rent.minimum_balance(
+            POSTED_MESSAGE_BASE_LEN + TRANSFER_WITH_PAYLOAD_BASE_LEN + payload.len(),
+        ),
+        payload,
+        sequence,
+        request_bytes: make_vaa_v1_request(OUR_CHAIN, TOKEN_BRIDGE_EMITTER.to_bytes(), sequence),
+    })
+}
@@ -0,0 +1,116 @@
+use crate::{
+    error::TokenBridgeRelayerError,
+    ext::{make_vaa_v1_request, PostedTransferWithPayload},
+    message::TokenBridgeRelayerMessage,
+    utils::TOKEN_BRIDGE_EMITTER,
+    EXECUTOR_ID, OUR_CHAIN,
+};
+use anchor_lang::prelude::*;
+use wormhole_anchor_sdk::wormhole::program::Wormhole;
+
+use super::{
+    executor::{self, types::RequestForExecutionArgs},
+    validate_signed_quote, ExecAmountArg, RelayInstructionsArg,
+};
+
+#[derive(Accounts)]
+pub struct RequestExecutionForSequence<'info> {
+    #[account(mut)]
+    /// Payer will pay the Executor for the relay.
+    pub payer: Signer<'info>,
+
+    #[account(owner = Wormhole::id() @ TokenBridgeRelayerError::InvalidPostedMessage)]
+    /// CHECK: Core Bridge message posted by the Token Bridge for the earlier
+    /// transfer. Parsed and checked against the sequence in the handler.
+    pub wormhole_message: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: payee account enforced by the Executor to match the quote.
+    pub payee: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+
+    #[account(
+        address = EXECUTOR_ID,
+        executable
+    )]
+    /// CHECK: Executor program. The address depends on the network this
+    /// program was built for.
+    pub executor_program: UncheckedAccount<'info>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct RequestExecutionForSequenceArgs {
+    /// Sequence of the Token Bridge message to relay.
+    pub sequence: u64,
+    pub dst_execution_address: [u8; 32],
+    pub exec_amount: ExecAmountArg,
+    pub signed_quote_bytes: Vec<u8>,
+    pub relay_instructions: RelayInstructionsArg,
+    /// Receives any Executor refund. Defaults to the payer.
+    pub refund_address: Option<Pubkey>,
+}
+
+pub fn request_execution_for_sequence(
+    ctx: Context<RequestExecutionForSequence>,
+    args: RequestExecutionForSequenceArgs,
+) -> Result<Vec<u8>> {
+    let RequestExecutionForSequenceArgs {
+        sequence,
+        dst_execution_address,
+        exec_amount,
+        signed_quote_bytes,
+        relay_instructions,
+        refund_address,
+    } = args;
+
+    // Only transfers sent through this program can be relayed, which the
+    // Token Bridge records as the transfer's `from_address`.
+    let transfer =
+        PostedTransferWithPayload::parse(&ctx.accounts.wormhole_message.try_borrow_data()?)
+            .ok_or(TokenBridgeRelayerError::InvalidPostedMessage)?;
+    require!(
+        transfer.sequence == sequence
+            && transfer.emitter_chain == OUR_CHAIN
+            && transfer.emitter_address == TOKEN_BRIDGE_EMITTER.to_bytes(),
+        TokenBridgeRelayerError::InvalidPostedMessage
+    );
+    require!(
+        transfer.from_address == crate::ID.to_bytes(),
+        TokenBridgeRelayerError::TransferNotFromThisProgram
+    );
+    let TokenBridgeRelayerMessage { recipient } =
+        TokenBridgeRelayerMessage::decode(&transfer.payload)?;
+
+    let relay_instructions = relay_instructions.into_bytes(&recipient)?;
+
+    let quote = validate_signed_quote(
+        &signed_quote_bytes,
+        transfer.to_chain,
+        &ctx.accounts.payee.key(),
+    )?;
+    let exec_amount = exec_amount.resolve(&quote, &relay_instructions)?;
+
+    let request_bytes = make_vaa_v1_request(OUR_CHAIN, TOKEN_BRIDGE_EMITTER.to_bytes(), sequence);
+    executor::cpi::request_for_execution(
+        CpiContext::new(
+            ctx.accounts.executor_program.to_account_info(),
+            executor::cpi::accounts::RequestForExecution {
+                payer: ctx.accounts.payer.to_account_info(),
+                payee: ctx.accounts.payee.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+        ),
+        RequestForExecutionArgs {
+            amount: exec_amount,
+            dst_chain: transfer.to_chain,
+            dst_addr: dst_execution_address,
+            refund_addr: refund_address.unwrap_or_else(|| ctx.accounts.payer.key()),
+            signed_quote_bytes,
+            request_bytes: request_bytes.clone(),
+            relay_instructions,
+        },
+    )?;
+
+    Ok(request_bytes)
+}
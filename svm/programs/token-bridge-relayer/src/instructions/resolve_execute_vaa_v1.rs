@@ -8,13 +8,21 @@ use executor_account_resolver_svm::{
     find_account, missing_account, InstructionGroup, InstructionGroups, MissingAccounts, Resolver,
     RESOLVER_PUBKEY_PAYER, RESOLVER_PUBKEY_POSTED_VAA,
 };
-use wormhole_anchor_sdk::{token_bridge::program::TokenBridge, wormhole::program::Wormhole};
-use wormhole_raw_vaas::{token_bridge::TokenBridgePayload, Body};
+use wormhole_anchor_sdk::{
+    token_bridge::{self, program::TokenBridge},
+    wormhole::program::Wormhole,
+};
+use wormhole_raw_vaas::Body;
 
 use crate::{
     error::TokenBridgeRelayerError,
-    instruction::{CompleteNativeTransferWithRelay, CompleteWrappedTransferWithRelay},
+    instruction::CompleteTransferWithRelay,
+    message::{TokenBridgeRelayerMessage, TokenBridgeRelayerPayload},
     state::{RedeemerConfig, LUT, SEED_PREFIX_LUT, SEED_PREFIX_TMP},
+    utils::{
+        supported_mint, TOKEN_BRIDGE_CONFIG, TOKEN_BRIDGE_CUSTODY_SIGNER,
+        TOKEN_BRIDGE_MINT_AUTHORITY,
+    },
     OUR_CHAIN,
 };
 
@@ -30,15 +38,26 @@ pub fn resolve_execute_vaa_v1(
     let message_hash = solana_program::keccak::hashv(&[&vaa_body]).to_bytes();
     // Parse the body.
     let body = Body::parse(&vaa_body).map_err(|_| TokenBridgeRelayerError::FailedToParseVaaBody)?;
-    let payload = TokenBridgePayload::try_from(body.payload())
-        .map_err(|_| TokenBridgeRelayerError::FailedToParseVaaBody)?
-        .message();
-    let transfer_with_message = payload
-        .transfer_with_message()
-        .ok_or(TokenBridgeRelayerError::FailedToParseVaaBody)?;
+    let transfer_with_message =
+        token_bridge::TransferWith::<TokenBridgeRelayerPayload>::deserialize(
+            &mut body.payload().as_ref(),
+        )
+        .map_err(|_| TokenBridgeRelayerError::FailedToParseVaaBody)?;
+    // Only transfers redeemable by this program can be relayed. Checking here
+    // saves relayers from paying for an instruction that is bound to fail.
+    require!(
+        transfer_with_message.to() == crate::ID,
+        TokenBridgeRelayerError::InvalidTransferToAddress
+    );
+    require!(
+        transfer_with_message.to_chain() == OUR_CHAIN,
+        TokenBridgeRelayerError::InvalidTransferToChain
+    );
+    let recipient = Pubkey::new_from_array(
+        TokenBridgeRelayerMessage::decode(transfer_with_message.data().as_ref())?.recipient,
+    );
     // Calculate shared accounts
     let (redeemer, _) = Pubkey::find_program_address(&[RedeemerConfig::SEED_PREFIX], &crate::ID);
-    let (token_bridge_config, _) = Pubkey::find_program_address(&[b"config"], &TokenBridge::id());
     let (token_bridge_claim, _) = Pubkey::find_program_address(
         &[
             &body.emitter_address(),
@@ -51,293 +70,209 @@ pub fn resolve_execute_vaa_v1(
         &[&body.emitter_chain().to_be_bytes(), &body.emitter_address()],
         &TokenBridge::id(),
     );
-    // Build instruction
-    if transfer_with_message.token_chain() == OUR_CHAIN {
-        let data = CompleteNativeTransferWithRelay {
-            _vaa_hash: message_hash,
-        };
-        let mint = Pubkey::new_from_array(transfer_with_message.token_address());
-        let mint_info = if let Some(acc_info) = find_account(ctx.remaining_accounts, mint) {
-            acc_info
-        } else {
-            return Ok(Resolver::Missing(MissingAccounts {
-                accounts: vec![mint, lut_pointer],
-                address_lookup_tables: vec![],
-            }));
-        };
-        let lut = if let Some(acc_info) = find_account(ctx.remaining_accounts, lut_pointer) {
-            let mut buf = &acc_info.try_borrow_mut_data()?[..];
-            LUT::try_deserialize(&mut buf)?
-        } else {
-            return Ok(missing_account(lut_pointer));
-        };
-        let token_program = *mint_info.owner;
-        let recipient = Pubkey::new_from_array(
-            transfer_with_message.payload().as_ref()[0..32]
-                .try_into()
-                .unwrap(),
-        );
-        let (tmp_token_account, _) =
-            Pubkey::find_program_address(&[SEED_PREFIX_TMP, &mint.to_bytes()], &crate::ID);
+    let (tmp_token_account, _) =
+        Pubkey::find_program_address(&[SEED_PREFIX_TMP, &message_hash], &crate::ID);
+    let (event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &crate::ID);
+    // Token Bridge accounts depend on whether the token originates from this
+    // chain. Accounts that do not apply are passed as this program's ID,
+    // which Anchor reads as `None`.
+    let (
+        mint,
+        token_bridge_custody,
+        token_bridge_custody_signer,
+        token_bridge_wrapped_meta,
+        token_bridge_mint_authority,
+    ) = if transfer_with_message.token_chain() == OUR_CHAIN {
+        let mint = Pubkey::new_from_array(*transfer_with_message.token_address());
         let (token_bridge_custody, _) =
             Pubkey::find_program_address(&[&mint.to_bytes()], &TokenBridge::id());
-        let (token_bridge_custody_signer, _) =
-            Pubkey::find_program_address(&[b"custody_signer"], &TokenBridge::id());
-        Ok(Resolver::Resolved(InstructionGroups(vec![
-            InstructionGroup {
-                instructions: vec![Instruction {
-                    program_id: crate::ID,
-                    data: data.data(),
-                    accounts: vec![
-                        AccountMeta {
-                            pubkey: RESOLVER_PUBKEY_PAYER,
-                            is_writable: true,
-                            is_signer: true,
-                        },
-                        AccountMeta {
-                            pubkey: redeemer,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: mint,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: get_associated_token_address_with_program_id(
-                                &recipient,
-                                &mint,
-                                &token_program,
-                            ),
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: recipient,
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: tmp_token_account,
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_config,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: RESOLVER_PUBKEY_POSTED_VAA,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_claim,
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_foreign_endpoint,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_custody,
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_custody_signer,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: Wormhole::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: TokenBridge::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_program,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: AssociatedToken::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: System::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: solana_program::sysvar::rent::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                    ],
-                }
-                .into()],
-                address_lookup_tables: vec![lut.address],
-            },
-        ])))
+        (
+            mint,
+            token_bridge_custody,
+            TOKEN_BRIDGE_CUSTODY_SIGNER,
+            crate::ID,
+            crate::ID,
+        )
     } else {
-        let data = CompleteWrappedTransferWithRelay {
-            _vaa_hash: message_hash,
-        };
-        let (token_bridge_wrapped_mint, _) = Pubkey::find_program_address(
+        let (mint, _) = Pubkey::find_program_address(
             &[
                 b"wrapped",
                 &transfer_with_message.token_chain().to_be_bytes(),
-                &transfer_with_message.token_address(),
+                transfer_with_message.token_address(),
             ],
             &TokenBridge::id(),
         );
-        let mint_info = if let Some(acc_info) =
-            find_account(ctx.remaining_accounts, token_bridge_wrapped_mint)
-        {
-            acc_info
-        } else {
-            return Ok(Resolver::Missing(MissingAccounts {
-                accounts: vec![token_bridge_wrapped_mint, lut_pointer],
-                address_lookup_tables: vec![],
-            }));
-        };
-        let lut = if let Some(acc_info) = find_account(ctx.remaining_accounts, lut_pointer) {
-            let mut buf = &acc_info.try_borrow_mut_data()?[..];
-            LUT::try_deserialize(&mut buf)?
-        } else {
-            return Ok(missing_account(lut_pointer));
-        };
-        let token_program = *mint_info.owner;
-        let recipient = Pubkey::new_from_array(
-            transfer_with_message.payload().as_ref()[0..32]
-                .try_into()
-                .unwrap(),
-        );
-        let (tmp_token_account, _) = Pubkey::find_program_address(
-            &[SEED_PREFIX_TMP, &token_bridge_wrapped_mint.to_bytes()],
-            &crate::ID,
-        );
-        let (token_bridge_wrapped_meta, _) = Pubkey::find_program_address(
-            &[b"meta", &token_bridge_wrapped_mint.to_bytes()],
-            &TokenBridge::id(),
-        );
-        let (token_bridge_mint_authority, _) =
-            Pubkey::find_program_address(&[b"mint_signer"], &TokenBridge::id());
-        Ok(Resolver::Resolved(InstructionGroups(vec![
-            InstructionGroup {
-                instructions: vec![Instruction {
-                    program_id: crate::ID,
-                    data: data.data(),
-                    accounts: vec![
-                        AccountMeta {
-                            pubkey: RESOLVER_PUBKEY_PAYER,
-                            is_writable: true,
-                            is_signer: true,
-                        },
-                        AccountMeta {
-                            pubkey: redeemer,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_wrapped_mint,
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: get_associated_token_address_with_program_id(
-                                &recipient,
-                                &token_bridge_wrapped_mint,
-                                &token_program,
-                            ),
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: recipient,
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: tmp_token_account,
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_wrapped_meta,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_config,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: RESOLVER_PUBKEY_POSTED_VAA,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_claim,
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_foreign_endpoint,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_bridge_mint_authority,
-                            is_writable: true,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: Wormhole::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: TokenBridge::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: token_program,
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: AssociatedToken::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: System::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                        AccountMeta {
-                            pubkey: solana_program::sysvar::rent::id(),
-                            is_writable: false,
-                            is_signer: false,
-                        },
-                    ],
-                }
-                .into()],
-                address_lookup_tables: vec![lut.address],
-            },
-        ])))
+        let (token_bridge_wrapped_meta, _) =
+            Pubkey::find_program_address(&[b"meta", &mint.to_bytes()], &TokenBridge::id());
+        (
+            mint,
+            crate::ID,
+            crate::ID,
+            token_bridge_wrapped_meta,
+            TOKEN_BRIDGE_MINT_AUTHORITY,
+        )
+    };
+    let mint_info = if let Some(acc_info) = find_account(ctx.remaining_accounts, mint) {
+        acc_info
+    } else {
+        return Ok(Resolver::Missing(MissingAccounts {
+            accounts: vec![mint, lut_pointer, token_bridge_claim],
+            address_lookup_tables: vec![],
+        }));
+    };
+    let lut = if let Some(acc_info) = find_account(ctx.remaining_accounts, lut_pointer) {
+        let mut buf = &acc_info.try_borrow_mut_data()?[..];
+        LUT::try_deserialize(&mut buf)?
+    } else {
+        return Ok(missing_account(lut_pointer));
+    };
+    // The claim account only exists once the transfer is redeemed, e.g. by
+    // the recipient themselves. There is nothing left to execute then, and
+    // the complete instruction would only fail with `AlreadyRedeemed`.
+    let Some(claim_info) = find_account(ctx.remaining_accounts, token_bridge_claim) else {
+        return Ok(missing_account(token_bridge_claim));
+    };
+    if !claim_info.data_is_empty() {
+        return Ok(Resolver::Resolved(InstructionGroups(vec![])));
     }
+    require!(
+        supported_mint(mint_info),
+        TokenBridgeRelayerError::UnsupportedMint
+    );
+    let token_program = *mint_info.owner;
+    let recipient_token_account =
+        get_associated_token_address_with_program_id(&recipient, &mint, &token_program);
+    // Build instruction
+    let data = CompleteTransferWithRelay {
+        _vaa_hash: message_hash,
+    };
+    let instruction = Instruction {
+        program_id: crate::ID,
+        data: data.data(),
+        accounts: vec![
+            AccountMeta {
+                pubkey: RESOLVER_PUBKEY_PAYER,
+                is_writable: true,
+                is_signer: true,
+            },
+            AccountMeta {
+                pubkey: redeemer,
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: mint,
+                is_writable: true,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: recipient_token_account,
+                is_writable: true,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: recipient,
+                is_writable: true,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: tmp_token_account,
+                is_writable: true,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: TOKEN_BRIDGE_CONFIG,
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: RESOLVER_PUBKEY_POSTED_VAA,
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: token_bridge_claim,
+                is_writable: true,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: token_bridge_foreign_endpoint,
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: token_bridge_custody,
+                is_writable: token_bridge_custody != crate::ID,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: token_bridge_custody_signer,
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: token_bridge_wrapped_meta,
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: token_bridge_mint_authority,
+                is_writable: false,
+                is_signer: false,
+            },
+            // Delivery receipts cost the relayer rent, so they are left out
+            // unless a relayer adds one to the instruction itself.
+            AccountMeta {
+                pubkey: crate::ID,
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: Wormhole::id(),
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: TokenBridge::id(),
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: token_program,
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: AssociatedToken::id(),
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: System::id(),
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: solana_program::sysvar::rent::id(),
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: event_authority,
+                is_writable: false,
+                is_signer: false,
+            },
+            AccountMeta {
+                pubkey: crate::ID,
+                is_writable: false,
+                is_signer: false,
+            },
+        ],
+    };
+    Ok(Resolver::Resolved(InstructionGroups(vec![
+        InstructionGroup {
+            instructions: vec![instruction.into()],
+            address_lookup_tables: vec![lut.address],
+        },
+    ])))
 }
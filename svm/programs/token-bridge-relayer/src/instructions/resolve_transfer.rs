@@ -0,0 +1,236 @@
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        instruction::Instruction,
+        program_option::COption,
+        sysvar::{clock, rent},
+    },
+    InstructionData,
+};
+use anchor_spl::{
+    associated_token::get_associated_token_address_with_program_id, token_interface::Mint,
+};
+use executor_account_resolver_svm::{
+    find_account, missing_account, InstructionGroup, InstructionGroups, MissingAccounts, Resolver,
+};
+use wormhole_anchor_sdk::{token_bridge::program::TokenBridge, wormhole::program::Wormhole};
+
+use crate::{
+    error::TokenBridgeRelayerError,
+    ext::SignedQuote,
+    instruction::{TransferNativeTokensWithRelay, TransferWrappedTokensWithRelay},
+    state::{SenderConfig, LUT, SEED_PREFIX_LUT, SEED_PREFIX_MESSAGE, SEED_PREFIX_TMP},
+    utils::{
+        supported_mint, token_bridge_pda, TOKEN_BRIDGE_AUTHORITY_SIGNER, TOKEN_BRIDGE_CONFIG,
+        TOKEN_BRIDGE_CUSTODY_SIGNER, TOKEN_BRIDGE_EMITTER, TOKEN_BRIDGE_MINT_AUTHORITY,
+        TOKEN_BRIDGE_SEQUENCE, WORMHOLE_BRIDGE, WORMHOLE_FEE_COLLECTOR,
+    },
+    TransferTokensWithRelayArgs, TransferWrappedTokensWithRelayArgs, EXECUTOR_ID,
+};
+
+#[derive(Accounts)]
+pub struct ResolveTransfer {}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct ResolveTransferArgs {
+    /// Payer of the transfer, which also owns the source token account.
+    pub payer: Pubkey,
+    /// Mint being transferred. Native and Token Bridge wrapped mints are
+    /// told apart by the mint authority.
+    pub mint: Pubkey,
+    /// Wormhole message keypair, if `transfer.message_seed` is not given.
+    pub wormhole_message: Option<Pubkey>,
+    /// Transfer arguments. `wrap_native` is ignored for wrapped mints.
+    pub transfer: TransferTokensWithRelayArgs,
+}
+
+pub fn resolve_transfer(
+    ctx: Context<ResolveTransfer>,
+    args: ResolveTransferArgs,
+) -> Result<Resolver<InstructionGroups>> {
+    let ResolveTransferArgs {
+        payer,
+        mint,
+        wormhole_message,
+        transfer,
+    } = args;
+    let lut_pointer = Pubkey::find_program_address(&[SEED_PREFIX_LUT], &crate::ID).0;
+    let mint_info = if let Some(acc_info) = find_account(ctx.remaining_accounts, mint) {
+        acc_info
+    } else {
+        return Ok(Resolver::Missing(MissingAccounts {
+            accounts: vec![mint, lut_pointer],
+            address_lookup_tables: vec![],
+        }));
+    };
+    let lut = if let Some(acc_info) = find_account(ctx.remaining_accounts, lut_pointer) {
+        let mut buf = &acc_info.try_borrow_mut_data()?[..];
+        LUT::try_deserialize(&mut buf)?
+    } else {
+        return Ok(missing_account(lut_pointer));
+    };
+    require!(
+        supported_mint(mint_info),
+        TokenBridgeRelayerError::UnsupportedMint
+    );
+    let token_program = *mint_info.owner;
+    let mint_authority = {
+        let mut buf = &mint_info.try_borrow_data()?[..];
+        Mint::try_deserialize(&mut buf)?.mint_authority
+    };
+    let is_wrapped = mint_authority == COption::Some(TOKEN_BRIDGE_MINT_AUTHORITY);
+
+    // The message is either the caller's keypair or the message PDA. The
+    // account that does not apply is passed as this program's ID, which
+    // Anchor reads as `None`.
+    let (wormhole_message, wormhole_message_meta, wormhole_message_pda_meta) =
+        match transfer.message_seed {
+            Some(message_seed) => {
+                let (wormhole_message_pda, _) = Pubkey::find_program_address(
+                    &[
+                        SEED_PREFIX_MESSAGE,
+                        payer.as_ref(),
+                        &message_seed.to_le_bytes(),
+                    ],
+                    &crate::ID,
+                );
+                (
+                    wormhole_message_pda,
+                    AccountMeta::new_readonly(crate::ID, false),
+                    AccountMeta::new(wormhole_message_pda, false),
+                )
+            }
+            None => {
+                let wormhole_message =
+                    wormhole_message.ok_or(TokenBridgeRelayerError::InvalidWormholeMessage)?;
+                (
+                    wormhole_message,
+                    AccountMeta::new(wormhole_message, true),
+                    AccountMeta::new_readonly(crate::ID, false),
+                )
+            }
+        };
+    let payee = SignedQuote::parse(&transfer.signed_quote_bytes)
+        .ok_or(TokenBridgeRelayerError::InvalidSignedQuote)?
+        .payee;
+
+    // Calculate shared accounts
+    let (config, _) = Pubkey::find_program_address(&[SenderConfig::SEED_PREFIX], &crate::ID);
+    let (tmp_token_account, _) =
+        Pubkey::find_program_address(&[SEED_PREFIX_TMP, wormhole_message.as_ref()], &crate::ID);
+    let from_token_account = if !is_wrapped && transfer.wrap_native {
+        // No source token account is needed when wrapping SOL.
+        crate::ID
+    } else {
+        get_associated_token_address_with_program_id(&payer, &mint, &token_program)
+    };
+    let (event_authority, _) = Pubkey::find_program_address(&[b"__event_authority"], &crate::ID);
+
+    // Build instruction
+    let instruction = if is_wrapped {
+        let TransferTokensWithRelayArgs {
+            amount,
+            recipient_chain,
+            recipient_address,
+            nonce,
+            wrap_native: _,
+            dst_transfer_recipient,
+            dst_execution_address,
+            exec_amount,
+            signed_quote_bytes,
+            relay_instructions,
+            refund_address,
+            message_seed,
+            min_amount_out,
+        } = transfer;
+        let data = TransferWrappedTokensWithRelay {
+            args: TransferWrappedTokensWithRelayArgs {
+                amount,
+                recipient_chain,
+                recipient_address,
+                nonce,
+                dst_transfer_recipient,
+                dst_execution_address,
+                exec_amount,
+                signed_quote_bytes,
+                relay_instructions,
+                refund_address,
+                message_seed,
+                min_amount_out,
+            },
+        };
+        Instruction {
+            program_id: crate::ID,
+            data: data.data(),
+            accounts: vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(crate::ID, false), // owner
+                AccountMeta::new_readonly(config, false),
+                AccountMeta::new(mint, false),
+                AccountMeta::new(from_token_account, false),
+                AccountMeta::new(tmp_token_account, false),
+                AccountMeta::new_readonly(token_bridge_pda(&[b"meta", mint.as_ref()]), false),
+                AccountMeta::new_readonly(TOKEN_BRIDGE_CONFIG, false),
+                AccountMeta::new_readonly(TOKEN_BRIDGE_AUTHORITY_SIGNER, false),
+                AccountMeta::new(WORMHOLE_BRIDGE, false),
+                wormhole_message_meta,
+                wormhole_message_pda_meta,
+                AccountMeta::new_readonly(TOKEN_BRIDGE_EMITTER, false),
+                AccountMeta::new(TOKEN_BRIDGE_SEQUENCE, false),
+                AccountMeta::new(WORMHOLE_FEE_COLLECTOR, false),
+                AccountMeta::new(Pubkey::from(payee), false),
+                AccountMeta::new_readonly(crate::ID, false), // transfer_receipt
+                AccountMeta::new_readonly(Wormhole::id(), false),
+                AccountMeta::new_readonly(TokenBridge::id(), false),
+                AccountMeta::new_readonly(System::id(), false),
+                AccountMeta::new_readonly(token_program, false),
+                AccountMeta::new_readonly(EXECUTOR_ID, false),
+                AccountMeta::new_readonly(clock::ID, false),
+                AccountMeta::new_readonly(rent::ID, false),
+                AccountMeta::new_readonly(event_authority, false),
+                AccountMeta::new_readonly(crate::ID, false),
+            ],
+        }
+    } else {
+        let data = TransferNativeTokensWithRelay { args: transfer };
+        Instruction {
+            program_id: crate::ID,
+            data: data.data(),
+            accounts: vec![
+                AccountMeta::new(payer, true),
+                AccountMeta::new_readonly(crate::ID, false), // owner
+                AccountMeta::new_readonly(config, false),
+                AccountMeta::new(mint, false),
+                AccountMeta::new(from_token_account, false),
+                AccountMeta::new(tmp_token_account, false),
+                AccountMeta::new_readonly(TOKEN_BRIDGE_CONFIG, false),
+                AccountMeta::new(token_bridge_pda(&[mint.as_ref()]), false),
+                AccountMeta::new_readonly(TOKEN_BRIDGE_AUTHORITY_SIGNER, false),
+                AccountMeta::new_readonly(TOKEN_BRIDGE_CUSTODY_SIGNER, false),
+                AccountMeta::new(WORMHOLE_BRIDGE, false),
+                wormhole_message_meta,
+                wormhole_message_pda_meta,
+                AccountMeta::new_readonly(TOKEN_BRIDGE_EMITTER, false),
+                AccountMeta::new(TOKEN_BRIDGE_SEQUENCE, false),
+                AccountMeta::new(WORMHOLE_FEE_COLLECTOR, false),
+                AccountMeta::new(Pubkey::from(payee), false),
+                AccountMeta::new_readonly(crate::ID, false), // transfer_receipt
+                AccountMeta::new_readonly(System::id(), false),
+                AccountMeta::new_readonly(token_program, false),
+                AccountMeta::new_readonly(Wormhole::id(), false),
+                AccountMeta::new_readonly(TokenBridge::id(), false),
+                AccountMeta::new_readonly(EXECUTOR_ID, false),
+                AccountMeta::new_readonly(clock::ID, false),
+                AccountMeta::new_readonly(rent::ID, false),
+                AccountMeta::new_readonly(event_authority, false),
+                AccountMeta::new_readonly(crate::ID, false),
+            ],
+        }
+    };
+    Ok(Resolver::Resolved(InstructionGroups(vec![
+        InstructionGroup {
+            instructions: vec![instruction.into()],
+            address_lookup_tables: vec![lut.address],
+        },
+    ])))
+}
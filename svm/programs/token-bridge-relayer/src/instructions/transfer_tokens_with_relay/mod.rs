@@ -1,62 +1,730 @@
 mod native;
+mod unified;
 mod wrapped;
 
 pub use native::*;
+pub use unified::*;
 pub use wrapped::*;
 
 use crate::{
-    error::TokenBridgeRelayerError, message::TokenBridgeRelayerMessage, state::SenderConfig,
-    utils::valid_foreign_address,
+    error::TokenBridgeRelayerError,
+    events::TransferInitiated,
+    ext::{
+        decode_relay_instructions, encode_relay_instructions, make_vaa_v1_request,
+        total_gas_limit_and_msg_value, RelayInstruction, SignedQuote, NORMALIZED_DECIMALS,
+    },
+    message::TokenBridgeRelayerMessage,
+    state::{SenderConfig, TransferReceipt, SEED_PREFIX_MESSAGE},
+    utils::{supported_mint, valid_foreign_address},
+    OUR_CHAIN,
 };
-use anchor_lang::prelude::*;
-use anchor_spl::token_interface::{TokenAccount, TokenInterface};
+use anchor_lang::{
+    prelude::*,
+    system_program::{self, Allocate, Assign, Transfer},
+};
+use anchor_spl::{
+    token::spl_token::native_mint,
+    token_interface::{self, Mint, TokenAccount, TokenInterface},
+};
+use wormhole_anchor_sdk::{
+    token_bridge::{self, program::TokenBridge},
+    wormhole::{self, program::Wormhole},
+};
+
+use super::executor::{self, types::RequestForExecutionArgs};
+
+/// Returned by the outbound transfer instructions so that programs composing
+/// on top of this one can learn the resulting VAA ID without parsing logs.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TransferTokensWithRelayResult {
+    /// Sequence of the resulting VAA.
+    pub sequence: u64,
+    /// Token Bridge emitter of the resulting VAA.
+    pub emitter: Pubkey,
+    /// Amount actually bridged after the Token Bridge 8 decimal truncation.
+    pub bridged_amount: u64,
+    /// Executor request passed to `request_for_execution`.
+    pub request_bytes: Vec<u8>,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct TransferTokensWithRelayArgs {
+    pub amount: u64,
+    pub recipient_chain: u16,
+    pub recipient_address: [u8; 32],
+    pub nonce: u32,
+    pub wrap_native: bool,
+    pub dst_transfer_recipient: [u8; 32],
+    pub dst_execution_address: [u8; 32],
+    pub exec_amount: ExecAmountArg,
+    pub signed_quote_bytes: Vec<u8>,
+    pub relay_instructions: RelayInstructionsArg,
+    /// Receives any Executor refund. Defaults to the payer.
+    pub refund_address: Option<Pubkey>,
+    /// Seed of the Wormhole message PDA, used instead of a message keypair.
+    /// Each seed can only be used once per payer.
+    pub message_seed: Option<u64>,
+    /// Minimum amount that must be bridged after the Token Bridge 8 decimal
+    /// truncation. Zero disables the check.
+    pub min_amount_out: u64,
+}
+
+/// Executor relay instructions, either pre-encoded or as typed instructions
+/// that the program encodes and checks against the transfer.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum RelayInstructionsArg {
+    /// Encoded relay instructions, passed to the Executor as-is.
+    Raw(Vec<u8>),
+    /// Typed relay instructions. A non-zero total gas limit is required and
+    /// gas can only be dropped off to the transfer recipient.
+    Typed(Vec<RelayInstruction>),
+}
 
-struct PrepareTransfer<'ctx, 'info> {
-    pub config: &'ctx Account<'info, SenderConfig>,
-    pub tmp_token_account: &'ctx InterfaceAccount<'info, TokenAccount>,
-    pub token_bridge_authority_signer: &'ctx UncheckedAccount<'info>,
-    pub token_program: &'ctx Interface<'info, TokenInterface>,
+impl RelayInstructionsArg {
+    /// Returns the encoded relay instructions, validating typed ones against
+    /// the transfer's `recipient_address`.
+    fn into_bytes(self, recipient_address: &[u8; 32]) -> Result<Vec<u8>> {
+        match self {
+            Self::Raw(bytes) => Ok(bytes),
+            Self::Typed(relay_instructions) => {
+                let mut gas_limit: u128 = 0;
+                for relay_instruction in &relay_instructions {
+                    match relay_instruction {
+                        RelayInstruction::Gas {
+                            gas_limit: limit, ..
+                        } => {
+                            gas_limit = gas_limit.saturating_add(*limit);
+                        }
+                        RelayInstruction::GasDropOff { recipient, .. } => {
+                            require!(
+                                recipient == recipient_address,
+                                TokenBridgeRelayerError::InvalidDropOffRecipient
+                            );
+                        }
+                    }
+                }
+                require!(gas_limit > 0, TokenBridgeRelayerError::ZeroGasLimit);
+                Ok(encode_relay_instructions(&relay_instructions))
+            }
+        }
+    }
 }
 
-fn prepare_transfer(
-    prepare_transfer: PrepareTransfer,
-    amount: u64,
+/// Lamports paid to the Executor for the relay.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub enum ExecAmountArg {
+    /// Amount computed off-chain from the signed quote.
+    Exact(u64),
+    /// Amount derived on-chain from the signed quote and relay instructions.
+    /// The transfer fails if it exceeds `max_exec_amount`.
+    FromQuote {
+        max_exec_amount: u64,
+        /// Decimals of the quote's destination gas price.
+        dst_gas_price_decimals: u8,
+        /// Decimals of the destination chain's native token.
+        dst_native_decimals: u8,
+    },
+}
+
+impl ExecAmountArg {
+    /// Returns the lamports to pay the Executor for the given quote and
+    /// encoded relay instructions.
+    fn resolve(self, quote: &SignedQuote, relay_instructions: &[u8]) -> Result<u64> {
+        match self {
+            Self::Exact(exec_amount) => Ok(exec_amount),
+            Self::FromQuote {
+                max_exec_amount,
+                dst_gas_price_decimals,
+                dst_native_decimals,
+            } => {
+                require!(
+                    dst_gas_price_decimals <= NORMALIZED_DECIMALS
+                        && dst_native_decimals <= NORMALIZED_DECIMALS,
+                    TokenBridgeRelayerError::InvalidQuoteDecimals
+                );
+                let relay_instructions = decode_relay_instructions(relay_instructions)
+                    .ok_or(TokenBridgeRelayerError::InvalidRelayInstructions)?;
+                let exec_amount = total_gas_limit_and_msg_value(&relay_instructions)
+                    .and_then(|(gas_limit, msg_value)| {
+                        quote.estimate_cost(
+                            gas_limit,
+                            msg_value,
+                            dst_gas_price_decimals,
+                            dst_native_decimals,
+                        )
+                    })
+                    .and_then(|exec_amount| u64::try_from(exec_amount).ok())
+                    .ok_or(TokenBridgeRelayerError::FeeCalculationError)?;
+                require!(
+                    exec_amount <= max_exec_amount,
+                    TokenBridgeRelayerError::ExecAmountExceedsMax
+                );
+                Ok(exec_amount)
+            }
+        }
+    }
+}
+
+/// Returns the authority over the source token account, which is the owner
+/// if one was provided and the payer otherwise.
+fn token_authority<'info>(
+    payer: &Signer<'info>,
+    owner: &Option<Signer<'info>>,
+) -> AccountInfo<'info> {
+    owner.as_ref().unwrap_or(payer).to_account_info()
+}
+
+/// Transfers the Core Bridge message fee from the payer to the fee collector,
+/// which the Token Bridge expects to have been paid before it posts the
+/// message.
+fn pay_wormhole_fee<'info>(
+    payer: &Signer<'info>,
+    wormhole_bridge: &Account<'info, wormhole::BridgeData>,
+    wormhole_fee_collector: &UncheckedAccount<'info>,
+    system_program: &Program<'info, System>,
+) -> Result<()> {
+    let fee = wormhole_bridge.fee();
+    if fee > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: wormhole_fee_collector.to_account_info(),
+                },
+            ),
+            fee,
+        )?;
+    }
+    Ok(())
+}
+
+/// Parses the Executor signed quote and checks that a relayer will honor it
+/// for this transfer. This must happen before any tokens move, otherwise the
+/// tokens end up locked in a VAA that nobody relays.
+pub(super) fn validate_signed_quote(
+    signed_quote_bytes: &[u8],
     recipient_chain: u16,
-    recipient: [u8; 32],
-) -> Result<TokenBridgeRelayerMessage> {
-    let PrepareTransfer {
+    payee: &Pubkey,
+) -> Result<SignedQuote> {
+    let quote = SignedQuote::parse(signed_quote_bytes)
+        .ok_or(TokenBridgeRelayerError::InvalidSignedQuote)?;
+    require_eq!(
+        quote.src_chain,
+        OUR_CHAIN,
+        TokenBridgeRelayerError::InvalidQuoteSourceChain
+    );
+    require_eq!(
+        quote.dst_chain,
+        recipient_chain,
+        TokenBridgeRelayerError::InvalidQuoteDestinationChain
+    );
+    require!(
+        quote.expiry_time > Clock::get()?.unix_timestamp as u64,
+        TokenBridgeRelayerError::QuoteExpired
+    );
+    require!(
+        Pubkey::from(quote.payee) == *payee,
+        TokenBridgeRelayerError::InvalidQuotePayee
+    );
+    Ok(quote)
+}
+
+/// Key of the Wormhole message account, which seeds the temporary token
+/// account. Defaults to the zero key if neither account is given, in which
+/// case [`resolve_wormhole_message`] rejects the transfer.
+fn wormhole_message_key(
+    wormhole_message: &Option<Signer>,
+    wormhole_message_pda: &Option<UncheckedAccount>,
+) -> Pubkey {
+    wormhole_message
+        .as_ref()
+        .map(|wormhole_message| wormhole_message.key())
+        .or_else(|| {
+            wormhole_message_pda
+                .as_ref()
+                .map(|wormhole_message_pda| wormhole_message_pda.key())
+        })
+        .unwrap_or_default()
+}
+
+/// Picks the Wormhole message account, which is either a keypair that signed
+/// the transaction or, with `message_seed`, this program's message PDA for
+/// the payer and that seed. Returns the PDA bump in the latter case so that
+/// the Token Bridge CPI can be signed with it.
+fn resolve_wormhole_message<'info>(
+    wormhole_message: &Option<Signer<'info>>,
+    wormhole_message_pda: &Option<UncheckedAccount<'info>>,
+    payer: &Pubkey,
+    message_seed: Option<&[u8; 8]>,
+) -> Result<(AccountInfo<'info>, Option<u8>)> {
+    match (wormhole_message, wormhole_message_pda, message_seed) {
+        (Some(wormhole_message), None, None) => Ok((wormhole_message.to_account_info(), None)),
+        (None, Some(wormhole_message_pda), Some(message_seed)) => {
+            let (expected, bump) = Pubkey::find_program_address(
+                &[SEED_PREFIX_MESSAGE, payer.as_ref(), message_seed],
+                &crate::ID,
+            );
+            require_keys_eq!(
+                wormhole_message_pda.key(),
+                expected,
+                TokenBridgeRelayerError::InvalidWormholeMessage
+            );
+            Ok((wormhole_message_pda.to_account_info(), Some(bump)))
+        }
+        _ => err!(TokenBridgeRelayerError::InvalidWormholeMessage),
+    }
+}
+
+/// Creates the transfer receipt PDA for `receipt.sequence` if one was
+/// provided, overwriting `receipt.bump` with the PDA bump. The account is
+/// created here rather than with `init` because its address depends on the
+/// sequence, which is only known once the Token Bridge has posted the message.
+///
+/// Callers have to predict the sequence when building the transaction, and
+/// another transfer landing first moves it on. The receipt is optional, so a
+/// mismatched address skips it rather than failing the whole transfer.
+fn create_transfer_receipt<'info>(
+    transfer_receipt: &Option<UncheckedAccount<'info>>,
+    payer: &Signer<'info>,
+    system_program: &Program<'info, System>,
+    receipt: TransferReceipt,
+) -> Result<()> {
+    let Some(transfer_receipt) = transfer_receipt else {
+        return Ok(());
+    };
+    let sequence = receipt.sequence.to_be_bytes();
+    let (expected, bump) =
+        Pubkey::find_program_address(&[TransferReceipt::SEED_PREFIX, &sequence], &crate::ID);
+    if transfer_receipt.key() != expected {
+        return Ok(());
+    }
+    let receipt_seeds: &[&[u8]] = &[TransferReceipt::SEED_PREFIX, &sequence, &[bump]];
+
+    // Like Anchor's `init`, don't fail if someone has already sent lamports to
+    // the address.
+    let space = 8 + TransferReceipt::INIT_SPACE;
+    let lamports = Rent::get()?
+        .minimum_balance(space)
+        .saturating_sub(transfer_receipt.lamports());
+    if lamports > 0 {
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: transfer_receipt.to_account_info(),
+                },
+            ),
+            lamports,
+        )?;
+    }
+    system_program::allocate(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Allocate {
+                account_to_allocate: transfer_receipt.to_account_info(),
+            },
+            &[receipt_seeds],
+        ),
+        space as u64,
+    )?;
+    system_program::assign(
+        CpiContext::new_with_signer(
+            system_program.to_account_info(),
+            Assign {
+                account_to_assign: transfer_receipt.to_account_info(),
+            },
+            &[receipt_seeds],
+        ),
+        &crate::ID,
+    )?;
+
+    TransferReceipt { bump, ..receipt }
+        .try_serialize(&mut &mut transfer_receipt.try_borrow_mut_data()?[..])
+}
+
+/// Token Bridge accounts that differ between native and wrapped mints.
+enum TokenBridgeAccounts<'ctx, 'info> {
+    /// Native mints are locked in the Token Bridge custody account.
+    Native {
+        custody: &'ctx UncheckedAccount<'info>,
+        custody_signer: &'ctx UncheckedAccount<'info>,
+    },
+    /// Token Bridge wrapped mints are burned.
+    Wrapped {
+        wrapped_meta: &'ctx UncheckedAccount<'info>,
+    },
+}
+
+/// Accounts of the outbound transfer instructions, which all share
+/// [`transfer_with_relay`].
+struct TransferWithRelay<'ctx, 'info> {
+    payer: &'ctx Signer<'info>,
+    owner: &'ctx Option<Signer<'info>>,
+    config: &'ctx Account<'info, SenderConfig>,
+    mint: &'ctx InterfaceAccount<'info, Mint>,
+    from_token_account: Option<&'ctx InterfaceAccount<'info, TokenAccount>>,
+    tmp_token_account: &'ctx InterfaceAccount<'info, TokenAccount>,
+    token_bridge_config: &'ctx UncheckedAccount<'info>,
+    token_bridge: TokenBridgeAccounts<'ctx, 'info>,
+    token_bridge_authority_signer: &'ctx UncheckedAccount<'info>,
+    wormhole_bridge: &'ctx Account<'info, wormhole::BridgeData>,
+    wormhole_message: &'ctx Option<Signer<'info>>,
+    wormhole_message_pda: &'ctx Option<UncheckedAccount<'info>>,
+    token_bridge_emitter: &'ctx UncheckedAccount<'info>,
+    token_bridge_sequence: &'ctx UncheckedAccount<'info>,
+    wormhole_fee_collector: &'ctx UncheckedAccount<'info>,
+    payee: &'ctx UncheckedAccount<'info>,
+    transfer_receipt: &'ctx Option<UncheckedAccount<'info>>,
+    system_program: &'ctx Program<'info, System>,
+    token_program: &'ctx Interface<'info, TokenInterface>,
+    wormhole_program: &'ctx Program<'info, Wormhole>,
+    token_bridge_program: &'ctx Program<'info, TokenBridge>,
+    executor_program: &'ctx UncheckedAccount<'info>,
+    clock: &'ctx UncheckedAccount<'info>,
+    rent: &'ctx UncheckedAccount<'info>,
+}
+
+/// Moves the tokens into the temporary token account, bridges them with the
+/// Token Bridge and requests their relay from the Executor. Returns the event
+/// for the caller to emit, since `emit_cpi!` needs the instruction's context.
+fn transfer_with_relay(
+    accounts: TransferWithRelay,
+    args: TransferTokensWithRelayArgs,
+) -> Result<(TransferTokensWithRelayResult, TransferInitiated)> {
+    let TransferWithRelay {
+        payer,
+        owner,
         config,
+        mint,
+        from_token_account,
         tmp_token_account,
+        token_bridge_config,
+        token_bridge,
         token_bridge_authority_signer,
+        wormhole_bridge,
+        wormhole_message,
+        wormhole_message_pda,
+        token_bridge_emitter,
+        token_bridge_sequence,
+        wormhole_fee_collector,
+        payee,
+        transfer_receipt,
+        system_program,
         token_program,
-    } = prepare_transfer;
+        wormhole_program,
+        token_bridge_program,
+        executor_program,
+        clock,
+        rent,
+    } = accounts;
+    let TransferTokensWithRelayArgs {
+        amount,
+        recipient_chain,
+        recipient_address,
+        nonce,
+        wrap_native,
+        dst_transfer_recipient,
+        dst_execution_address,
+        exec_amount,
+        signed_quote_bytes,
+        relay_instructions,
+        refund_address,
+        message_seed,
+        min_amount_out,
+    } = args;
+
     require!(
-        valid_foreign_address(recipient_chain, &recipient),
+        valid_foreign_address(recipient_chain, &recipient_address),
         TokenBridgeRelayerError::InvalidRecipient,
     );
+    require!(
+        supported_mint(&mint.to_account_info()),
+        TokenBridgeRelayerError::UnsupportedMint
+    );
+
+    // Token Bridge program truncates amounts to 8 decimals, so there will
+    // be a residual amount if decimals of the SPL is >8. We need to take
+    // into account how much will actually be bridged. Wrapped mints never
+    // have more than 8 decimals, so this is a no-op for them.
+    let bridged_amount = token_bridge::truncate_amount(amount, mint.decimals);
+    require!(
+        bridged_amount > 0,
+        TokenBridgeRelayerError::ZeroBridgeAmount
+    );
+    require!(
+        bridged_amount >= min_amount_out,
+        TokenBridgeRelayerError::InsufficientAmountOut
+    );
+
+    let relay_instructions = relay_instructions.into_bytes(&recipient_address)?;
+
+    let quote = validate_signed_quote(&signed_quote_bytes, recipient_chain, &payee.key())?;
+    let exec_amount = exec_amount.resolve(&quote, &relay_instructions)?;
+
+    let payer_key = payer.key();
+
+    let message_seed = message_seed.map(u64::to_le_bytes);
+    let (wormhole_message, message_bump) = resolve_wormhole_message(
+        wormhole_message,
+        wormhole_message_pda,
+        &payer_key,
+        message_seed.as_ref(),
+    )?;
+    let message_bump = message_bump.map(|bump| [bump]);
+
+    // Pay the Wormhole message fee so that callers don't have to.
+    pay_wormhole_fee(
+        payer,
+        wormhole_bridge,
+        wormhole_fee_collector,
+        system_program,
+    )?;
 
     // These seeds are used to:
     // 1.  Sign the Sender Config's token account to delegate approval
-    //     of amount.
-    // 2.  Sign Token Bridge program's transfer_wrapped instruction.
+    //     of bridged_amount.
+    // 2.  Sign Token Bridge program's transfer_native or transfer_wrapped
+    //     instruction.
     // 3.  Close tmp_token_account.
     let config_seeds = &[SenderConfig::SEED_PREFIX.as_ref(), &[config.bump]];
+    let message_seeds = message_seed
+        .as_ref()
+        .zip(message_bump.as_ref())
+        .map(|(seed, bump)| {
+            [
+                SEED_PREFIX_MESSAGE.as_ref(),
+                payer_key.as_ref(),
+                seed.as_ref(),
+                bump.as_ref(),
+            ]
+        });
+    let token_bridge_signer_seeds: &[&[&[u8]]] = match &message_seeds {
+        Some(message_seeds) => &[config_seeds, message_seeds],
+        None => &[config_seeds],
+    };
+
+    // If the user wishes to transfer native SOL, we need to transfer the
+    // lamports to the tmp_token_account and then convert it to native SOL. Otherwise,
+    // we can just transfer the specified token to the tmp_token_account.
+    if wrap_native {
+        require!(
+            mint.key() == native_mint::ID,
+            TokenBridgeRelayerError::NativeMintRequired
+        );
+
+        // Transfer lamports to the tmp_token_account (these lamports will be our WSOL).
+        system_program::transfer(
+            CpiContext::new(
+                system_program.to_account_info(),
+                Transfer {
+                    from: payer.to_account_info(),
+                    to: tmp_token_account.to_account_info(),
+                },
+            ),
+            bridged_amount,
+        )?;
+
+        // Sync the token account based on the lamports we sent it,
+        // this is where the wrapping takes place.
+        token_interface::sync_native(CpiContext::new(
+            token_program.to_account_info(),
+            token_interface::SyncNative {
+                account: tmp_token_account.to_account_info(),
+            },
+        ))?;
+    } else {
+        let from_token_account =
+            from_token_account.ok_or(TokenBridgeRelayerError::FromTokenAccountRequired)?;
+        token_interface::transfer_checked(
+            CpiContext::new(
+                token_program.to_account_info(),
+                token_interface::TransferChecked {
+                    from: from_token_account.to_account_info(),
+                    mint: mint.to_account_info(),
+                    to: tmp_token_account.to_account_info(),
+                    authority: token_authority(payer, owner),
+                },
+            ),
+            bridged_amount,
+            mint.decimals,
+        )?;
+    }
 
     // Delegate spending to Token Bridge program's authority signer.
-    anchor_spl::token_interface::approve(
+    token_interface::approve(
         CpiContext::new_with_signer(
             token_program.to_account_info(),
-            anchor_spl::token_interface::Approve {
+            token_interface::Approve {
                 to: tmp_token_account.to_account_info(),
                 delegate: token_bridge_authority_signer.to_account_info(),
                 authority: config.to_account_info(),
             },
             &[config_seeds],
         ),
-        amount,
+        bridged_amount,
     )?;
 
     // Serialize TokenBridgeRelayerMessage as encoded payload for Token Bridge
     // transfer.
-    Ok(TokenBridgeRelayerMessage { recipient })
+    let payload = TokenBridgeRelayerMessage {
+        recipient: recipient_address,
+    }
+    .encode();
+
+    match token_bridge {
+        TokenBridgeAccounts::Native {
+            custody,
+            custody_signer,
+        } => {
+            // Bridge native token with encoded payload.
+            crate::ext::transfer_native_with_payload(
+                CpiContext::new_with_signer(
+                    token_bridge_program.to_account_info(),
+                    crate::ext::TransferNativeWithPayload {
+                        payer: payer.to_account_info(),
+                        config: token_bridge_config.to_account_info(),
+                        from: tmp_token_account.to_account_info(),
+                        mint: mint.to_account_info(),
+                        custody: custody.to_account_info(),
+                        authority_signer: token_bridge_authority_signer.to_account_info(),
+                        custody_signer: custody_signer.to_account_info(),
+                        wormhole_bridge: wormhole_bridge.to_account_info(),
+                        wormhole_message: wormhole_message.to_account_info(),
+                        wormhole_emitter: token_bridge_emitter.to_account_info(),
+                        wormhole_sequence: token_bridge_sequence.to_account_info(),
+                        wormhole_fee_collector: wormhole_fee_collector.to_account_info(),
+                        clock: clock.to_account_info(),
+                        sender: config.to_account_info(),
+                        rent: rent.to_account_info(),
+                        system_program: system_program.to_account_info(),
+                        token_program: token_program.to_account_info(),
+                        wormhole_program: wormhole_program.to_account_info(),
+                    },
+                    token_bridge_signer_seeds,
+                ),
+                nonce,
+                bridged_amount,
+                dst_transfer_recipient,
+                recipient_chain,
+                payload,
+                &crate::ID,
+            )?;
+        }
+        TokenBridgeAccounts::Wrapped { wrapped_meta } => {
+            // Bridge wrapped token with encoded payload.
+            crate::ext::transfer_wrapped_with_payload(
+                CpiContext::new_with_signer(
+                    token_bridge_program.to_account_info(),
+                    crate::ext::TransferWrappedWithPayload {
+                        payer: payer.to_account_info(),
+                        config: token_bridge_config.to_account_info(),
+                        from: tmp_token_account.to_account_info(),
+                        from_owner: config.to_account_info(),
+                        wrapped_mint: mint.to_account_info(),
+                        wrapped_metadata: wrapped_meta.to_account_info(),
+                        authority_signer: token_bridge_authority_signer.to_account_info(),
+                        wormhole_bridge: wormhole_bridge.to_account_info(),
+                        wormhole_message: wormhole_message.to_account_info(),
+                        wormhole_emitter: token_bridge_emitter.to_account_info(),
+                        wormhole_sequence: token_bridge_sequence.to_account_info(),
+                        wormhole_fee_collector: wormhole_fee_collector.to_account_info(),
+                        clock: clock.to_account_info(),
+                        sender: config.to_account_info(),
+                        rent: rent.to_account_info(),
+                        system_program: system_program.to_account_info(),
+                        token_program: token_program.to_account_info(),
+                        wormhole_program: wormhole_program.to_account_info(),
+                    },
+                    token_bridge_signer_seeds,
+                ),
+                nonce,
+                bridged_amount,
+                dst_transfer_recipient,
+                recipient_chain,
+                payload,
+                &crate::ID,
+            )?;
+        }
+    }
+
+    // Finish instruction by closing tmp_token_account.
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        token_interface::CloseAccount {
+            account: tmp_token_account.to_account_info(),
+            destination: payer.to_account_info(),
+            authority: config.to_account_info(),
+        },
+        &[config_seeds],
+    ))?;
+
+    // parse the sequence from the account and request execution
+    // reading the account after avoids having to handle when the account doesn't exist
+    let sequence = {
+        let mut buf = &token_bridge_sequence.try_borrow_data()?[..];
+        wormhole::SequenceTracker::try_deserialize(&mut buf)?.sequence - 1
+    };
+    let emitter = token_bridge_emitter.key();
+    let request_bytes = make_vaa_v1_request(OUR_CHAIN, emitter.to_bytes(), sequence);
+    executor::cpi::request_for_execution(
+        CpiContext::new(
+            executor_program.to_account_info(),
+            executor::cpi::accounts::RequestForExecution {
+                payer: payer.to_account_info(),
+                payee: payee.to_account_info(),
+                system_program: system_program.to_account_info(),
+            },
+        ),
+        RequestForExecutionArgs {
+            amount: exec_amount,
+            dst_chain: recipient_chain,
+            dst_addr: dst_execution_address,
+            refund_addr: refund_address.unwrap_or(payer_key),
+            signed_quote_bytes,
+            request_bytes: request_bytes.clone(),
+            relay_instructions,
+        },
+    )?;
+
+    create_transfer_receipt(
+        transfer_receipt,
+        payer,
+        system_program,
+        TransferReceipt {
+            bump: 0,
+            sequence,
+            sender: token_authority(payer, owner).key(),
+            payer: payer_key,
+            mint: mint.key(),
+            bridged_amount,
+            recipient_chain,
+            recipient_address,
+            dst_execution_address,
+            exec_amount,
+        },
+    )?;
+
+    Ok((
+        TransferTokensWithRelayResult {
+            sequence,
+            emitter,
+            bridged_amount,
+            request_bytes,
+        },
+        TransferInitiated {
+            mint: mint.key(),
+            amount,
+            truncated_amount: bridged_amount,
+            recipient_chain,
+            recipient_address,
+            dst_transfer_recipient,
+            dst_execution_address,
+            exec_amount,
+            emitter,
+            sequence,
+            payer: payer_key,
+        },
+    ))
 }
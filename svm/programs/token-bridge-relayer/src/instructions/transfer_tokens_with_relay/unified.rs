@@ -0,0 +1,265 @@
+use crate::{
+    error::TokenBridgeRelayerError,
+    state::{SenderConfig, SEED_PREFIX_TMP},
+    utils::{
+        token_bridge_pda, TOKEN_BRIDGE_AUTHORITY_SIGNER, TOKEN_BRIDGE_CONFIG,
+        TOKEN_BRIDGE_CUSTODY_SIGNER, TOKEN_BRIDGE_EMITTER, TOKEN_BRIDGE_MINT_AUTHORITY,
+        TOKEN_BRIDGE_SEQUENCE, WORMHOLE_BRIDGE, WORMHOLE_FEE_COLLECTOR,
+    },
+    EXECUTOR_ID,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{
+        program_option::COption,
+        sysvar::{clock, rent},
+    },
+};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+use wormhole_anchor_sdk::{
+    token_bridge::program::TokenBridge,
+    wormhole::{self, program::Wormhole},
+};
+
+use super::{
+    transfer_with_relay, wormhole_message_key, TokenBridgeAccounts, TransferTokensWithRelayArgs,
+    TransferTokensWithRelayResult, TransferWithRelay,
+};
+
+#[event_cpi]
+#[derive(Accounts)]
+pub struct TransferTokensWithRelay<'info> {
+    #[account(mut)]
+    /// Payer will pay Wormhole fee to transfer tokens and create temporary
+    /// token account.
+    pub payer: Signer<'info>,
+
+    /// Owner or delegate of the source token account, if it is not the payer.
+    /// This may be a PDA signing via CPI. Read-only.
+    pub owner: Option<Signer<'info>>,
+
+    #[account(
+        seeds = [SenderConfig::SEED_PREFIX],
+        bump = config.bump,
+    )]
+    /// Sender Config account. Acts as the signer for the Token Bridge token
+    /// transfer. Read-only.
+    pub config: Box<Account<'info, SenderConfig>>,
+
+    #[account(mut)]
+    /// Mint info. This is the SPL token that will be bridged over to the
+    /// foreign contract. It is treated as a Token Bridge wrapped mint if its
+    /// mint authority is the Token Bridge mint signer, and as a native mint
+    /// otherwise. Mutable.
+    pub mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    /// Source token account. Any token account of this mint that the owner
+    /// (or the payer, if no owner is given) may spend from. Not required when
+    /// wrapping native SOL, since the lamports are taken from the payer.
+    /// Mutable.
+    pub from_token_account: Option<Box<InterfaceAccount<'info, TokenAccount>>>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            SEED_PREFIX_TMP,
+            wormhole_message_key(&wormhole_message, &wormhole_message_pda).as_ref(),
+        ],
+        bump,
+        token::mint = mint,
+        token::authority = config,
+        token::token_program = token_program
+    )]
+    /// Program's temporary token account. This account is created before the
+    /// instruction is invoked to temporarily take custody of the payer's
+    /// tokens. When the tokens are finally bridged out, the token account
+    /// will have zero balance and can be closed. It is seeded by the Wormhole
+    /// message, which is unique per transfer, so that transfers of the same
+    /// mint never contend for it.
+    pub tmp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(
+        address = TOKEN_BRIDGE_CONFIG
+            @ TokenBridgeRelayerError::InvalidTokenBridgeConfig
+    )]
+    /// CHECK: Token Bridge config. Read-only.
+    pub token_bridge_config: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = token_bridge_pda(&[mint.key().as_ref()])
+            @ TokenBridgeRelayerError::InvalidTokenBridgeCustody
+    )]
+    /// CHECK: Token Bridge custody. This is the Token Bridge program's token
+    /// account that holds this mint's balance. This account needs to be
+    /// unchecked because a token account may not have been created for this
+    /// mint yet. Only required for native mints. Mutable.
+    pub token_bridge_custody: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        address = token_bridge_pda(&[b"meta", mint.key().as_ref()])
+            @ TokenBridgeRelayerError::InvalidTokenBridgeWrappedMeta
+    )]
+    /// CHECK: Token Bridge program's wrapped metadata. Only required for
+    /// wrapped mints. Read-only.
+    pub token_bridge_wrapped_meta: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        address = TOKEN_BRIDGE_AUTHORITY_SIGNER
+            @ TokenBridgeRelayerError::InvalidTokenBridgeAuthoritySigner
+    )]
+    /// CHECK: Token Bridge authority signer. Read-only.
+    pub token_bridge_authority_signer: UncheckedAccount<'info>,
+
+    #[account(
+        address = TOKEN_BRIDGE_CUSTODY_SIGNER
+            @ TokenBridgeRelayerError::InvalidTokenBridgeCustodySigner
+    )]
+    /// CHECK: Token Bridge custody signer. Only required for native mints.
+    /// Read-only.
+    pub token_bridge_custody_signer: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        address = WORMHOLE_BRIDGE
+            @ TokenBridgeRelayerError::InvalidWormholeBridge
+    )]
+    /// Wormhole bridge data. The message fee is read from here and paid by
+    /// the payer. Mutable.
+    pub wormhole_bridge: Box<Account<'info, wormhole::BridgeData>>,
+
+    #[account(mut)]
+    /// Wormhole Message. Token Bridge program writes info about the tokens
+    /// transferred in this account for our program. A new keypair signing
+    /// the transaction, required unless `message_seed` is given. The Core
+    /// Bridge has no way to close messages, so its rent cannot be reclaimed.
+    /// Mutable.
+    pub wormhole_message: Option<Signer<'info>>,
+
+    #[account(mut)]
+    /// CHECK: Wormhole Message as this program's PDA, derived from the payer
+    /// and `message_seed`, which must then be given instead of
+    /// `wormhole_message`. Checked in the instruction handler. Mutable.
+    pub wormhole_message_pda: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        address = TOKEN_BRIDGE_EMITTER
+            @ TokenBridgeRelayerError::InvalidTokenBridgeEmitter
+    )]
+    /// CHECK: Token Bridge emitter.
+    pub token_bridge_emitter: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = TOKEN_BRIDGE_SEQUENCE @ TokenBridgeRelayerError::InvalidTokenBridgeSequence
+    )]
+    /// CHECK: Token Bridge sequence.
+    pub token_bridge_sequence: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = WORMHOLE_FEE_COLLECTOR
+            @ TokenBridgeRelayerError::InvalidWormholeFeeCollector
+    )]
+    /// CHECK: Wormhole fee collector. Mutable.
+    pub wormhole_fee_collector: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: payee account enforced by the Executor to match the quote.
+    pub payee: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    /// CHECK: Optional transfer receipt PDA for the resulting sequence. It is
+    /// checked and created in the instruction handler once the sequence is
+    /// known.
+    pub transfer_receipt: Option<UncheckedAccount<'info>>,
+
+    pub system_program: Program<'info, System>,
+    pub token_program: Interface<'info, TokenInterface>,
+    pub wormhole_program: Program<'info, Wormhole>,
+    pub token_bridge_program: Program<'info, TokenBridge>,
+
+    #[account(
+        address = EXECUTOR_ID,
+        executable
+    )]
+    /// CHECK: Executor program. The address depends on the network this
+    /// program was built for.
+    pub executor_program: UncheckedAccount<'info>,
+
+    #[account(address = clock::ID)]
+    /// CHECK: Token Bridge program needs clock sysvar.
+    pub clock: UncheckedAccount<'info>,
+
+    #[account(address = rent::ID)]
+    /// CHECK: Token Bridge program needs rent sysvar.
+    pub rent: UncheckedAccount<'info>,
+}
+
+pub fn transfer_tokens_with_relay(
+    ctx: Context<TransferTokensWithRelay>,
+    args: TransferTokensWithRelayArgs,
+) -> Result<TransferTokensWithRelayResult> {
+    // Token Bridge wrapped mints are minted by the Token Bridge mint signer.
+    let is_wrapped = ctx.accounts.mint.mint_authority == COption::Some(TOKEN_BRIDGE_MINT_AUTHORITY);
+    let token_bridge = if is_wrapped {
+        TokenBridgeAccounts::Wrapped {
+            wrapped_meta: ctx
+                .accounts
+                .token_bridge_wrapped_meta
+                .as_ref()
+                .ok_or(TokenBridgeRelayerError::TokenBridgeWrappedMetaRequired)?,
+        }
+    } else {
+        let (custody, custody_signer) = ctx
+            .accounts
+            .token_bridge_custody
+            .as_ref()
+            .zip(ctx.accounts.token_bridge_custody_signer.as_ref())
+            .ok_or(TokenBridgeRelayerError::TokenBridgeCustodyRequired)?;
+        TokenBridgeAccounts::Native {
+            custody,
+            custody_signer,
+        }
+    };
+
+    let (result, event) = transfer_with_relay(
+        TransferWithRelay {
+            payer: &ctx.accounts.payer,
+            owner: &ctx.accounts.owner,
+            config: &ctx.accounts.config,
+            mint: &ctx.accounts.mint,
+            from_token_account: ctx.accounts.from_token_account.as_deref(),
+            tmp_token_account: &ctx.accounts.tmp_token_account,
+            token_bridge_config: &ctx.accounts.token_bridge_config,
+            token_bridge,
+            token_bridge_authority_signer: &ctx.accounts.token_bridge_authority_signer,
+            wormhole_bridge: &ctx.accounts.wormhole_bridge,
+            wormhole_message: &ctx.accounts.wormhole_message,
+            wormhole_message_pda: &ctx.accounts.wormhole_message_pda,
+            token_bridge_emitter: &ctx.accounts.token_bridge_emitter,
+            token_bridge_sequence: &ctx.accounts.token_bridge_sequence,
+            wormhole_fee_collector: &ctx.accounts.wormhole_fee_collector,
+            payee: &ctx.accounts.payee,
+            transfer_receipt: &ctx.accounts.transfer_receipt,
+            system_program: &ctx.accounts.system_program,
+            token_program: &ctx.accounts.token_program,
+            wormhole_program: &ctx.accounts.wormhole_program,
+            token_bridge_program: &ctx.accounts.token_bridge_program,
+            executor_program: &ctx.accounts.executor_program,
+            clock: &ctx.accounts.clock,
+            rent: &ctx.accounts.rent,
+        },
+        args,
+    )?;
+
+    emit_cpi!(event);
+
+    Ok(result)
+}
@@ -1,31 +1,40 @@
-declare_program!(executor);
-
 use crate::{
-    ext::make_vaa_v1_request,
+    error::TokenBridgeRelayerError,
     state::{SenderConfig, SEED_PREFIX_TMP},
-    OUR_CHAIN,
+    utils::{
+        token_bridge_pda, TOKEN_BRIDGE_AUTHORITY_SIGNER, TOKEN_BRIDGE_CONFIG, TOKEN_BRIDGE_EMITTER,
+        TOKEN_BRIDGE_SEQUENCE, WORMHOLE_BRIDGE, WORMHOLE_FEE_COLLECTOR,
+    },
+    EXECUTOR_ID,
+};
+use anchor_lang::{
+    prelude::*,
+    solana_program::sysvar::{clock, rent},
 };
-use anchor_lang::prelude::*;
 use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
-use executor::{program::Executor, types::RequestForExecutionArgs};
 use wormhole_anchor_sdk::{
     token_bridge::program::TokenBridge,
     wormhole::{self, program::Wormhole},
 };
 
-use super::{prepare_transfer, PrepareTransfer};
+use super::{
+    transfer_with_relay, wormhole_message_key, ExecAmountArg, RelayInstructionsArg,
+    TokenBridgeAccounts, TransferTokensWithRelayArgs, TransferTokensWithRelayResult,
+    TransferWithRelay,
+};
 
+#[event_cpi]
 #[derive(Accounts)]
-#[instruction(
-    _amount: u64,
-    recipient_chain: u16
-)]
 pub struct TransferWrappedWithRelay<'info> {
     #[account(mut)]
     /// Payer will pay Wormhole fee to transfer tokens and create temporary
     /// token account.
     pub payer: Signer<'info>,
 
+    /// Owner or delegate of the source token account, if it is not the payer.
+    /// This may be a PDA signing via CPI. Read-only.
+    pub owner: Option<Signer<'info>>,
+
     #[account(
         seeds = [SenderConfig::SEED_PREFIX],
         bump = config.bump,
@@ -41,20 +50,19 @@ pub struct TransferWrappedWithRelay<'info> {
 
     #[account(
         mut,
-        associated_token::mint = token_bridge_wrapped_mint,
-        associated_token::authority = payer,
-        associated_token::token_program = token_program
+        token::mint = token_bridge_wrapped_mint,
+        token::token_program = token_program
     )]
-    /// Payer's associated token account. We may want to make this a generic
-    /// token account in the future.
-    pub from_token_account: InterfaceAccount<'info, TokenAccount>,
+    /// Source token account. Any token account of this mint that the owner
+    /// (or the payer, if no owner is given) may spend from. Mutable.
+    pub from_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(
         init,
         payer = payer,
         seeds = [
             SEED_PREFIX_TMP,
-            token_bridge_wrapped_mint.key().as_ref(),
+            wormhole_message_key(&wormhole_message, &wormhole_message_pda).as_ref(),
         ],
         bump,
         token::mint = token_bridge_wrapped_mint,
@@ -64,9 +72,15 @@ pub struct TransferWrappedWithRelay<'info> {
     /// Program's temporary token account. This account is created before the
     /// instruction is invoked to temporarily take custody of the payer's
     /// tokens. When the tokens are finally bridged out, the token account
-    /// will have zero balance and can be closed.
+    /// will have zero balance and can be closed. It is seeded by the Wormhole
+    /// message, which is unique per transfer, so that transfers of the same
+    /// mint never contend for it.
     pub tmp_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
+    #[account(
+        address = token_bridge_pda(&[b"meta", token_bridge_wrapped_mint.key().as_ref()])
+            @ TokenBridgeRelayerError::InvalidTokenBridgeWrappedMeta
+    )]
     /// CHECK: Token Bridge program's wrapped metadata, which stores info
     /// about the token from its native chain:
     ///   * Wormhole Chain ID
@@ -74,29 +88,62 @@ pub struct TransferWrappedWithRelay<'info> {
     ///   * Token's native decimals
     pub token_bridge_wrapped_meta: UncheckedAccount<'info>,
 
+    #[account(
+        address = TOKEN_BRIDGE_CONFIG
+            @ TokenBridgeRelayerError::InvalidTokenBridgeConfig
+    )]
     /// CHECK: Token Bridge config. Read-only.
     pub token_bridge_config: UncheckedAccount<'info>,
 
+    #[account(
+        address = TOKEN_BRIDGE_AUTHORITY_SIGNER
+            @ TokenBridgeRelayerError::InvalidTokenBridgeAuthoritySigner
+    )]
     /// CHECK: Token Bridge authority signer. Read-only.
     pub token_bridge_authority_signer: UncheckedAccount<'info>,
 
+    #[account(
+        mut,
+        address = WORMHOLE_BRIDGE
+            @ TokenBridgeRelayerError::InvalidWormholeBridge
+    )]
+    /// Wormhole bridge data. The message fee is read from here and paid by
+    /// the payer. Mutable.
+    pub wormhole_bridge: Box<Account<'info, wormhole::BridgeData>>,
+
     #[account(mut)]
-    /// CHECK: Wormhole bridge data. Mutable.
-    pub wormhole_bridge: UncheckedAccount<'info>,
+    /// Wormhole Message. Token Bridge program writes info about the tokens
+    /// transferred in this account for our program. A new keypair signing
+    /// the transaction, required unless `message_seed` is given. The Core
+    /// Bridge has no way to close messages, so its rent cannot be reclaimed.
+    /// Mutable.
+    pub wormhole_message: Option<Signer<'info>>,
 
     #[account(mut)]
-    /// CHECK: Wormhole Message. Token Bridge program writes info about the
-    /// tokens transferred in this account for our program. Mutable.
-    pub wormhole_message: Signer<'info>,
+    /// CHECK: Wormhole Message as this program's PDA, derived from the payer
+    /// and `message_seed`, which must then be given instead of
+    /// `wormhole_message`. Checked in the instruction handler. Mutable.
+    pub wormhole_message_pda: Option<UncheckedAccount<'info>>,
 
+    #[account(
+        address = TOKEN_BRIDGE_EMITTER
+            @ TokenBridgeRelayerError::InvalidTokenBridgeEmitter
+    )]
     /// CHECK: Token Bridge emitter.
     pub token_bridge_emitter: UncheckedAccount<'info>,
 
+    #[account(
+        mut,
+        address = TOKEN_BRIDGE_SEQUENCE @ TokenBridgeRelayerError::InvalidTokenBridgeSequence
+    )]
     /// CHECK: Token Bridge sequence.
-    #[account(mut)]
     pub token_bridge_sequence: UncheckedAccount<'info>,
 
-    #[account(mut)]
+    #[account(
+        mut,
+        address = WORMHOLE_FEE_COLLECTOR
+            @ TokenBridgeRelayerError::InvalidWormholeFeeCollector
+    )]
     /// CHECK: Wormhole fee collector. Mutable.
     pub wormhole_fee_collector: UncheckedAccount<'info>,
 
@@ -104,15 +151,30 @@ pub struct TransferWrappedWithRelay<'info> {
     /// CHECK: payee account enforced by the Executor to match the quote.
     pub payee: UncheckedAccount<'info>,
 
+    #[account(mut)]
+    /// CHECK: Optional transfer receipt PDA for the resulting sequence. It is
+    /// checked and created in the instruction handler once the sequence is
+    /// known.
+    pub transfer_receipt: Option<UncheckedAccount<'info>>,
+
     pub wormhole_program: Program<'info, Wormhole>,
     pub token_bridge_program: Program<'info, TokenBridge>,
     pub system_program: Program<'info, System>,
     pub token_program: Interface<'info, TokenInterface>,
-    pub executor_program: Program<'info, Executor>,
 
+    #[account(
+        address = EXECUTOR_ID,
+        executable
+    )]
+    /// CHECK: Executor program. The address depends on the network this
+    /// program was built for.
+    pub executor_program: UncheckedAccount<'info>,
+
+    #[account(address = clock::ID)]
     /// CHECK: Token Bridge program needs clock sysvar.
     pub clock: UncheckedAccount<'info>,
 
+    #[account(address = rent::ID)]
     /// CHECK: Token Bridge program needs rent sysvar.
     pub rent: UncheckedAccount<'info>,
 }
@@ -125,15 +187,23 @@ pub struct TransferWrappedTokensWithRelayArgs {
     pub nonce: u32,
     pub dst_transfer_recipient: [u8; 32],
     pub dst_execution_address: [u8; 32],
-    pub exec_amount: u64,
+    pub exec_amount: ExecAmountArg,
     pub signed_quote_bytes: Vec<u8>,
-    pub relay_instructions: Vec<u8>,
+    pub relay_instructions: RelayInstructionsArg,
+    /// Receives any Executor refund. Defaults to the payer.
+    pub refund_address: Option<Pubkey>,
+    /// Seed of the Wormhole message PDA, used instead of a message keypair.
+    /// Each seed can only be used once per payer.
+    pub message_seed: Option<u64>,
+    /// Minimum amount that must be bridged after the Token Bridge 8 decimal
+    /// truncation. Zero disables the check.
+    pub min_amount_out: u64,
 }
 
 pub fn transfer_wrapped_tokens_with_relay(
     ctx: Context<TransferWrappedWithRelay>,
     args: TransferWrappedTokensWithRelayArgs,
-) -> Result<()> {
+) -> Result<TransferTokensWithRelayResult> {
     let TransferWrappedTokensWithRelayArgs {
         amount,
         recipient_chain,
@@ -144,113 +214,58 @@ pub fn transfer_wrapped_tokens_with_relay(
         exec_amount,
         signed_quote_bytes,
         relay_instructions,
+        refund_address,
+        message_seed,
+        min_amount_out,
     } = args;
 
-    let config = &ctx.accounts.config;
-    let payer = &ctx.accounts.payer;
-    let tmp_token_account = &ctx.accounts.tmp_token_account;
-    let token_bridge_authority_signer = &ctx.accounts.token_bridge_authority_signer;
-    let token_program = &ctx.accounts.token_program;
-
-    // First transfer tokens from payer to tmp_token_account.
-    anchor_spl::token_interface::transfer_checked(
-        CpiContext::new(
-            token_program.to_account_info(),
-            anchor_spl::token_interface::TransferChecked {
-                from: ctx.accounts.from_token_account.to_account_info(),
-                mint: ctx.accounts.token_bridge_wrapped_mint.to_account_info(),
-                to: tmp_token_account.to_account_info(),
-                authority: payer.to_account_info(),
+    let (result, event) = transfer_with_relay(
+        TransferWithRelay {
+            payer: &ctx.accounts.payer,
+            owner: &ctx.accounts.owner,
+            config: &ctx.accounts.config,
+            mint: &ctx.accounts.token_bridge_wrapped_mint,
+            from_token_account: Some(&*ctx.accounts.from_token_account),
+            tmp_token_account: &ctx.accounts.tmp_token_account,
+            token_bridge_config: &ctx.accounts.token_bridge_config,
+            token_bridge: TokenBridgeAccounts::Wrapped {
+                wrapped_meta: &ctx.accounts.token_bridge_wrapped_meta,
             },
-        ),
-        amount,
-        ctx.accounts.token_bridge_wrapped_mint.decimals,
-    )?;
-
-    let msg = prepare_transfer(
-        PrepareTransfer {
-            config,
-            tmp_token_account,
-            token_bridge_authority_signer,
-            token_program,
+            token_bridge_authority_signer: &ctx.accounts.token_bridge_authority_signer,
+            wormhole_bridge: &ctx.accounts.wormhole_bridge,
+            wormhole_message: &ctx.accounts.wormhole_message,
+            wormhole_message_pda: &ctx.accounts.wormhole_message_pda,
+            token_bridge_emitter: &ctx.accounts.token_bridge_emitter,
+            token_bridge_sequence: &ctx.accounts.token_bridge_sequence,
+            wormhole_fee_collector: &ctx.accounts.wormhole_fee_collector,
+            payee: &ctx.accounts.payee,
+            transfer_receipt: &ctx.accounts.transfer_receipt,
+            system_program: &ctx.accounts.system_program,
+            token_program: &ctx.accounts.token_program,
+            wormhole_program: &ctx.accounts.wormhole_program,
+            token_bridge_program: &ctx.accounts.token_bridge_program,
+            executor_program: &ctx.accounts.executor_program,
+            clock: &ctx.accounts.clock,
+            rent: &ctx.accounts.rent,
         },
-        amount,
-        recipient_chain,
-        recipient_address,
-    )?;
-
-    let config_seeds = &[SenderConfig::SEED_PREFIX.as_ref(), &[config.bump]];
-
-    // Bridge wrapped token with encoded payload.
-    crate::ext::transfer_wrapped_with_payload(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_bridge_program.to_account_info(),
-            crate::ext::TransferWrappedWithPayload {
-                payer: payer.to_account_info(),
-                config: ctx.accounts.token_bridge_config.to_account_info(),
-                from: tmp_token_account.to_account_info(),
-                from_owner: ctx.accounts.config.to_account_info(),
-                wrapped_mint: ctx.accounts.token_bridge_wrapped_mint.to_account_info(),
-                wrapped_metadata: ctx.accounts.token_bridge_wrapped_meta.to_account_info(),
-                authority_signer: ctx.accounts.token_bridge_authority_signer.to_account_info(),
-                wormhole_bridge: ctx.accounts.wormhole_bridge.to_account_info(),
-                wormhole_message: ctx.accounts.wormhole_message.to_account_info(),
-                wormhole_emitter: ctx.accounts.token_bridge_emitter.to_account_info(),
-                wormhole_sequence: ctx.accounts.token_bridge_sequence.to_account_info(),
-                wormhole_fee_collector: ctx.accounts.wormhole_fee_collector.to_account_info(),
-                clock: ctx.accounts.clock.to_account_info(),
-                sender: ctx.accounts.config.to_account_info(),
-                rent: ctx.accounts.rent.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-                token_program: token_program.to_account_info(),
-                wormhole_program: ctx.accounts.wormhole_program.to_account_info(),
-            },
-            &[config_seeds],
-        ),
-        nonce,
-        amount,
-        dst_transfer_recipient,
-        recipient_chain,
-        msg.try_to_vec()?,
-        &crate::ID,
-    )?;
-
-    // Finish instruction by closing tmp_token_account.
-    anchor_spl::token_interface::close_account(CpiContext::new_with_signer(
-        token_program.to_account_info(),
-        anchor_spl::token_interface::CloseAccount {
-            account: tmp_token_account.to_account_info(),
-            destination: payer.to_account_info(),
-            authority: config.to_account_info(),
-        },
-        &[config_seeds],
-    ))?;
-
-    // parse the sequence from the account and request execution
-    // reading the account after avoids having to handle when the account doesn't exist
-    let mut buf = &ctx.accounts.token_bridge_sequence.try_borrow_mut_data()?[..];
-    let seq = wormhole::SequenceTracker::try_deserialize(&mut buf)?;
-    executor::cpi::request_for_execution(
-        CpiContext::new(
-            ctx.accounts.executor_program.to_account_info(),
-            executor::cpi::accounts::RequestForExecution {
-                payer: ctx.accounts.payer.to_account_info(),
-                payee: ctx.accounts.payee.to_account_info(),
-                system_program: ctx.accounts.system_program.to_account_info(),
-            },
-        ),
-        RequestForExecutionArgs {
-            amount: exec_amount,
-            dst_chain: recipient_chain,
-            dst_addr: dst_execution_address,
-            refund_addr: ctx.accounts.payer.key(),
+        TransferTokensWithRelayArgs {
+            amount,
+            recipient_chain,
+            recipient_address,
+            nonce,
+            wrap_native: false,
+            dst_transfer_recipient,
+            dst_execution_address,
+            exec_amount,
             signed_quote_bytes,
-            request_bytes: make_vaa_v1_request(
-                OUR_CHAIN,
-                ctx.accounts.token_bridge_emitter.key().to_bytes(),
-                seq.sequence - 1,
-            ),
             relay_instructions,
+            refund_address,
+            message_seed,
+            min_amount_out,
         },
-    )
+    )?;
+
+    emit_cpi!(event);
+
+    Ok(result)
 }
@@ -1,10 +1,66 @@
 use anchor_lang::prelude::*;
 use executor_account_resolver_svm::{InstructionGroups, Resolver, RESOLVER_EXECUTE_VAA_V1};
 
-declare_id!("tbr7Qje6qBzPwfM52csL5KFi8ps5c5vDyiVVBLYVdRf");
+#[cfg(not(any(
+    feature = "mainnet",
+    feature = "testnet",
+    feature = "localnet",
+    feature = "chain-id-override"
+)))]
+compile_error!(
+    "exactly one of `mainnet`, `testnet`, `localnet` or `chain-id-override` must be enabled"
+);
 
-// TODO: cfg_if
-pub const OUR_CHAIN: u16 = 51;
+#[cfg(any(
+    all(feature = "mainnet", feature = "testnet"),
+    all(feature = "mainnet", feature = "localnet"),
+    all(feature = "mainnet", feature = "chain-id-override"),
+    all(feature = "testnet", feature = "localnet"),
+    all(feature = "testnet", feature = "chain-id-override"),
+    all(feature = "localnet", feature = "chain-id-override"),
+))]
+compile_error!(
+    "only one of `mainnet`, `testnet`, `localnet` or `chain-id-override` may be enabled; \
+     use `--no-default-features` when selecting a network other than `chain-id-override`"
+);
+
+// Solana is Wormhole chain 1 on every network, and the program and Executor
+// are deployed at the same addresses on each of them. The Wormhole program
+// addresses must match the ones the `wormhole-anchor-sdk` feature selects.
+cfg_if::cfg_if! {
+    if #[cfg(feature = "mainnet")] {
+        declare_id!("tbr7Qje6qBzPwfM52csL5KFi8ps5c5vDyiVVBLYVdRf");
+        pub const OUR_CHAIN: u16 = 1;
+        pub const EXECUTOR_ID: Pubkey = pubkey!("execXUrAsMnqMmTHj5m7N1YQgsDz3cwGLYCYyuDRciV");
+        pub const WORMHOLE_ID: Pubkey = pubkey!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+        pub const TOKEN_BRIDGE_ID: Pubkey = pubkey!("wormDTUJ6AWPNvk59vGQbDvGJmqbDTdgWgAqcLBCgUb");
+    } else if #[cfg(feature = "testnet")] {
+        declare_id!("tbr7Qje6qBzPwfM52csL5KFi8ps5c5vDyiVVBLYVdRf");
+        pub const OUR_CHAIN: u16 = 1;
+        pub const EXECUTOR_ID: Pubkey = pubkey!("execXUrAsMnqMmTHj5m7N1YQgsDz3cwGLYCYyuDRciV");
+        pub const WORMHOLE_ID: Pubkey = pubkey!("3u8hJUVTA4jH1wYAyUur7FFZVQ8H635K3tSHHF4ssjQ5");
+        pub const TOKEN_BRIDGE_ID: Pubkey = pubkey!("DZnkkTmCiFWfYTfT41X3Rd1kDgozqzxWaHqsw6W4x2oe");
+    } else if #[cfg(feature = "localnet")] {
+        declare_id!("tbr7Qje6qBzPwfM52csL5KFi8ps5c5vDyiVVBLYVdRf");
+        pub const OUR_CHAIN: u16 = 1;
+        pub const EXECUTOR_ID: Pubkey = pubkey!("execXUrAsMnqMmTHj5m7N1YQgsDz3cwGLYCYyuDRciV");
+        pub const WORMHOLE_ID: Pubkey = pubkey!("Bridge1p5gheXUvJ6jGWGeCsgPKgnE3YgdGKRVCMY9o");
+        pub const TOKEN_BRIDGE_ID: Pubkey = pubkey!("B6RHG3mfcckmrYN1UhmJzyS1XX3fZKbkeUcpJe9Sy3FE");
+    } else if #[cfg(feature = "chain-id-override")] {
+        // SVM forks reuse the program and Executor addresses but register
+        // their own Wormhole chain ID and Wormhole deployments, which are
+        // read from `OUR_CHAIN`, `BRIDGE_ADDRESS` and `TOKEN_BRIDGE_ADDRESS`
+        // at build time.
+        declare_id!("tbr7Qje6qBzPwfM52csL5KFi8ps5c5vDyiVVBLYVdRf");
+        pub const OUR_CHAIN: u16 = match u16::from_str_radix(env!("OUR_CHAIN"), 10) {
+            Ok(chain) => chain,
+            Err(_) => panic!("OUR_CHAIN must be a valid u16"),
+        };
+        pub const EXECUTOR_ID: Pubkey = pubkey!("execXUrAsMnqMmTHj5m7N1YQgsDz3cwGLYCYyuDRciV");
+        pub const WORMHOLE_ID: Pubkey = Pubkey::from_str_const(env!("BRIDGE_ADDRESS"));
+        pub const TOKEN_BRIDGE_ID: Pubkey = Pubkey::from_str_const(env!("TOKEN_BRIDGE_ADDRESS"));
+    }
+}
 
 mod instructions;
 pub(crate) use instructions::*;
@@ -13,6 +69,8 @@ pub mod state;
 
 pub mod error;
 
+pub mod events;
+
 pub mod ext;
 
 mod message;
@@ -33,6 +91,11 @@ pub mod token_bridge_relayer {
     /// This instruction is used to transfer native tokens from Solana to a
     /// foreign blockchain. If the user is transferring native SOL,
     /// the contract will automatically wrap the lamports into a WSOL.
+    /// Otherwise, tokens are taken from `from_token_account` using the optional
+    /// `owner` as the authority, falling back to the payer. If
+    /// `transfer_receipt` is provided and is the PDA for the resulting
+    /// sequence, a `TransferReceipt` is created there. Only SPL Token mints
+    /// are supported.
     ///
     /// # Arguments
     ///
@@ -44,19 +107,27 @@ pub mod token_bridge_relayer {
     /// * `wrap_native` - Whether to wrap native SOL
     /// * `dst_transfer_recipient` - Token Bridge payload 3 recipient
     /// * `dst_execution_address` - Executor destination address
-    /// * `exec_amount` - Amount of lamports to pay the execution payee
+    /// * `exec_amount` - Lamports to pay the execution payee, exact or derived
+    ///   from the signed quote
     /// * `signed_quote_bytes` - Executor signed quote
-    /// * `relay_instructions` - Executor relay instructions
+    /// * `relay_instructions` - Executor relay instructions, encoded or typed
+    /// * `refund_address` - Executor refund recipient, defaulting to the payer
+    /// * `message_seed` - Seed of the Wormhole message PDA, if not a keypair
+    /// * `min_amount_out` - Minimum amount to bridge after truncation
     pub fn transfer_native_tokens_with_relay(
         ctx: Context<TransferNativeWithRelay>,
-        args: TransferNativeTokensWithRelayArgs,
-    ) -> Result<()> {
+        args: TransferTokensWithRelayArgs,
+    ) -> Result<TransferTokensWithRelayResult> {
         instructions::transfer_native_tokens_with_relay(ctx, args)
     }
 
     /// This instruction is used to transfer wrapped tokens from Solana to a
     /// foreign blockchain. This instruction should only be called
     /// when the user is transferring a wrapped token.
+    /// Tokens are taken from `from_token_account` using the optional `owner`
+    /// as the authority, falling back to the payer. If `transfer_receipt` is
+    /// provided and is the PDA for the resulting sequence, a `TransferReceipt`
+    /// is created there.
     ///
     /// # Arguments
     ///
@@ -67,20 +138,83 @@ pub mod token_bridge_relayer {
     /// * `nonce` - Nonce of Wormhole message
     /// * `dst_transfer_recipient` - Token Bridge payload 3 recipient
     /// * `dst_execution_address` - Executor destination address
-    /// * `exec_amount` - Amount of lamports to pay the execution payee
+    /// * `exec_amount` - Lamports to pay the execution payee, exact or derived
+    ///   from the signed quote
     /// * `signed_quote_bytes` - Executor signed quote
-    /// * `relay_instructions` - Executor relay instructions
+    /// * `relay_instructions` - Executor relay instructions, encoded or typed
+    /// * `refund_address` - Executor refund recipient, defaulting to the payer
+    /// * `message_seed` - Seed of the Wormhole message PDA, if not a keypair
+    /// * `min_amount_out` - Minimum amount to bridge after truncation
     pub fn transfer_wrapped_tokens_with_relay(
         ctx: Context<TransferWrappedWithRelay>,
         args: TransferWrappedTokensWithRelayArgs,
-    ) -> Result<()> {
+    ) -> Result<TransferTokensWithRelayResult> {
         instructions::transfer_wrapped_tokens_with_relay(ctx, args)
     }
 
+    /// This instruction is used to transfer either native or wrapped tokens
+    /// from Solana to a foreign blockchain. Whether the mint is Token Bridge
+    /// wrapped is detected from its mint authority, so only the custody
+    /// accounts (native) or the wrapped meta account (wrapped) are required.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - `TransferTokensWithRelay` context
+    /// * `args` - Same as `transfer_native_tokens_with_relay`
+    pub fn transfer_tokens_with_relay(
+        ctx: Context<TransferTokensWithRelay>,
+        args: TransferTokensWithRelayArgs,
+    ) -> Result<TransferTokensWithRelayResult> {
+        instructions::transfer_tokens_with_relay(ctx, args)
+    }
+
+    /// This instruction closes a transfer receipt created by one of the
+    /// transfer instructions, returning its rent to the sender.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - `CloseTransferReceipt` context
+    pub fn close_transfer_receipt(ctx: Context<CloseTransferReceipt>) -> Result<()> {
+        instructions::close_transfer_receipt(ctx)
+    }
+
+    /// This instruction closes a delivery receipt created by one of the
+    /// complete instructions, returning its rent to the payer.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - `CloseDeliveryReceipt` context
+    pub fn close_delivery_receipt(ctx: Context<CloseDeliveryReceipt>) -> Result<()> {
+        instructions::close_delivery_receipt(ctx)
+    }
+
+    /// This instruction requests execution from the Executor for a transfer
+    /// that was already sent through this program, e.g. when the original
+    /// relay failed or its quote was underpriced. The posted message for
+    /// `sequence` must be a Token Bridge transfer from this program.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - `RequestExecutionForSequence` context
+    /// * `sequence` - Sequence of the Token Bridge message to relay
+    /// * `dst_execution_address` - Executor destination address
+    /// * `exec_amount` - Lamports to pay the execution payee, exact or derived
+    ///   from the signed quote
+    /// * `signed_quote_bytes` - Executor signed quote
+    /// * `relay_instructions` - Executor relay instructions, encoded or typed
+    /// * `refund_address` - Executor refund recipient, defaulting to the payer
+    pub fn request_execution_for_sequence(
+        ctx: Context<RequestExecutionForSequence>,
+        args: RequestExecutionForSequenceArgs,
+    ) -> Result<Vec<u8>> {
+        instructions::request_execution_for_sequence(ctx, args)
+    }
+
     /// This instruction is used to redeem token transfers from foreign emitters.
     /// It takes custody of the released native tokens and sends the tokens to the
     /// encoded `recipient`.  If the token being transferred is WSOL, the contract
-    /// will unwrap the WSOL and send the lamports to the recipient.
+    /// will unwrap the WSOL and send the lamports to the recipient. Only SPL
+    /// Token mints are supported.
     ///
     /// # Arguments
     ///
@@ -89,7 +223,7 @@ pub mod token_bridge_relayer {
     pub fn complete_native_transfer_with_relay(
         ctx: Context<CompleteNativeWithRelay>,
         _vaa_hash: [u8; 32],
-    ) -> Result<()> {
+    ) -> Result<CompleteTransferWithRelayResult> {
         instructions::complete_native_transfer_with_relay(ctx, _vaa_hash)
     }
 
@@ -104,11 +238,28 @@ pub mod token_bridge_relayer {
     pub fn complete_wrapped_transfer_with_relay(
         ctx: Context<CompleteWrappedWithRelay>,
         _vaa_hash: [u8; 32],
-    ) -> Result<()> {
+    ) -> Result<CompleteTransferWithRelayResult> {
         instructions::complete_wrapped_transfer_with_relay(ctx, _vaa_hash)
     }
 
-    /// This instruction returns the instruction for execution based on a v1 VAA
+    /// This instruction is used to redeem token transfers from foreign emitters
+    /// for either native or wrapped tokens. Tokens whose origin is this chain
+    /// are released from custody, everything else is minted as a wrapped
+    /// token. WSOL is unwrapped and sent to the recipient as lamports.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - `CompleteTransferWithRelay` context
+    /// * `vaa_hash` - Hash of the VAA that triggered the transfer
+    pub fn complete_transfer_with_relay(
+        ctx: Context<CompleteTransferWithRelay>,
+        _vaa_hash: [u8; 32],
+    ) -> Result<CompleteTransferWithRelayResult> {
+        instructions::complete_transfer_with_relay(ctx, _vaa_hash)
+    }
+
+    /// This instruction returns the instruction for execution based on a v1 VAA,
+    /// or no instructions if the transfer has already been redeemed.
     /// # Arguments
     ///
     /// * `ctx` - `ResolveExecuteVaaV1` context
@@ -120,4 +271,34 @@ pub mod token_bridge_relayer {
     ) -> Result<Resolver<InstructionGroups>> {
         instructions::resolve_execute_vaa_v1(ctx, vaa_body)
     }
+
+    /// This instruction returns the outbound transfer instruction for a mint,
+    /// either `transfer_native_tokens_with_relay` or
+    /// `transfer_wrapped_tokens_with_relay`, with all accounts derived.
+    /// # Arguments
+    ///
+    /// * `ctx` - `ResolveTransfer` context
+    /// * `args` - Payer, mint, message and transfer arguments
+    pub fn resolve_transfer(
+        ctx: Context<ResolveTransfer>,
+        args: ResolveTransferArgs,
+    ) -> Result<Resolver<InstructionGroups>> {
+        instructions::resolve_transfer(ctx, args)
+    }
+
+    /// This instruction previews an outbound transfer of `amount` of `mint`
+    /// without moving any funds, and is meant to be simulated.
+    ///
+    /// # Arguments
+    ///
+    /// * `ctx` - `PreviewTransfer` context
+    /// * `amount` - Amount of tokens to send
+    /// * `recipient_chain` - Chain ID of the target chain
+    /// * `recipient_address` - Address of the target wallet on the target chain
+    pub fn preview_transfer(
+        ctx: Context<PreviewTransfer>,
+        args: PreviewTransferArgs,
+    ) -> Result<PreviewTransferResult> {
+        instructions::preview_transfer(ctx, args)
+    }
 }
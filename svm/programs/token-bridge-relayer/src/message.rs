@@ -2,10 +2,63 @@ use anchor_lang::prelude::*;
 
 use wormhole_anchor_sdk::token_bridge;
 
-#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+use crate::error::TokenBridgeRelayerError;
+
+/// Token Bridge payload of transfers sent through this program: exactly the
+/// 32-byte recipient, as on Sui and EVM. Anything else, including trailing
+/// bytes, is rejected.
+///
+/// A versioned layout, `version (u8) | flags (u8) | recipient ([u8; 32])`
+/// with the flags announcing optional trailing fields (e.g. the original
+/// sender or a memo), is reserved for the future. It is not accepted yet,
+/// since it has to be rolled out on every chain at once.
+#[derive(Clone, Copy)]
 pub struct TokenBridgeRelayerMessage {
     pub recipient: [u8; 32],
 }
 
+impl TokenBridgeRelayerMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        self.recipient.to_vec()
+    }
+
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let recipient = payload
+            .try_into()
+            .map_err(|_| TokenBridgeRelayerError::InvalidRelayerMessage)?;
+        Ok(Self { recipient })
+    }
+}
+
+/// Raw Token Bridge payload of a posted transfer, decoded with
+/// [`TokenBridgeRelayerMessage::decode`]. Keeping it raw lets a malformed
+/// payload fail with `InvalidRelayerMessage` instead of an account
+/// deserialization error.
+#[derive(Clone)]
+pub struct TokenBridgeRelayerPayload(Vec<u8>);
+
+impl AsRef<[u8]> for TokenBridgeRelayerPayload {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AnchorDeserialize for TokenBridgeRelayerPayload {
+    fn deserialize_reader<R: std::io::Read>(reader: &mut R) -> std::io::Result<Self> {
+        let mut payload = Vec::new();
+        reader.read_to_end(&mut payload)?;
+        Ok(Self(payload))
+    }
+}
+
+impl AnchorSerialize for TokenBridgeRelayerPayload {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        writer.write_all(&self.0)
+    }
+}
+
+#[cfg(feature = "idl-build")]
+impl anchor_lang::IdlBuild for TokenBridgeRelayerPayload {}
+
 pub type PostedTokenBridgeRelayerMessage =
-    token_bridge::PostedTransferWith<TokenBridgeRelayerMessage>;
+    token_bridge::PostedTransferWith<TokenBridgeRelayerPayload>;
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+/// Proof of delivery of an inbound transfer, keyed by the VAA's emitter
+/// chain, emitter address and sequence. Created on request by the complete
+/// instructions so that relayers can reconcile payments against deliveries,
+/// and closed by the payer with `close_delivery_receipt`.
+pub struct DeliveryReceipt {
+    /// PDA bump.
+    pub bump: u8,
+    /// Wormhole chain ID of the VAA's emitter.
+    pub emitter_chain: u16,
+    /// Address of the VAA's emitter.
+    pub emitter_address: [u8; 32],
+    /// Sequence of the VAA.
+    pub sequence: u64,
+    /// Payer of the redemption, i.e. the relayer.
+    pub payer: Pubkey,
+    /// Slot the transfer was delivered in.
+    pub slot: u64,
+    /// Recipient encoded in the transfer payload.
+    pub recipient: Pubkey,
+    /// Amount delivered to the recipient.
+    pub amount: u64,
+}
+
+impl DeliveryReceipt {
+    /// AKA `b"delivery_receipt"`.
+    pub const SEED_PREFIX: &'static [u8; 16] = b"delivery_receipt";
+}
@@ -1,3 +1,6 @@
+mod delivery_receipt;
+pub use delivery_receipt::*;
+
 mod lut;
 pub use lut::*;
 
@@ -7,8 +10,13 @@ pub use redeemer_config::*;
 mod sender_config;
 pub use sender_config::*;
 
+mod transfer_receipt;
+pub use transfer_receipt::*;
+
 /// AKA `b"tmp"`.
 pub const SEED_PREFIX_TMP: &[u8; 3] = b"tmp";
+/// AKA `b"message"`.
+pub const SEED_PREFIX_MESSAGE: &[u8; 7] = b"message";
 /// AKA `b"lut"`
 pub const SEED_PREFIX_LUT: &[u8; 3] = b"lut";
 /// AKA `b"lut_authority"`
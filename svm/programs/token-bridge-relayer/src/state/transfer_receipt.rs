@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+#[account]
+#[derive(InitSpace)]
+/// Record of an outbound transfer, keyed by the sequence of the Token Bridge
+/// message. Created on request by the transfer instructions and closed by
+/// the sender with `close_transfer_receipt`, who reclaims the rent.
+pub struct TransferReceipt {
+    /// PDA bump.
+    pub bump: u8,
+    /// Sequence of the Token Bridge message.
+    pub sequence: u64,
+    /// Authority over the source tokens, i.e. the owner, or the payer if no
+    /// owner was given.
+    pub sender: Pubkey,
+    /// Payer of the transfer, who paid the receipt rent.
+    pub payer: Pubkey,
+    /// Mint of the bridged token.
+    pub mint: Pubkey,
+    /// Amount actually bridged after the Token Bridge 8 decimal truncation.
+    pub bridged_amount: u64,
+    /// Wormhole chain ID of the target chain.
+    pub recipient_chain: u16,
+    /// Address of the target wallet on the target chain.
+    pub recipient_address: [u8; 32],
+    /// Executor destination address.
+    pub dst_execution_address: [u8; 32],
+    /// Lamports paid to the execution payee.
+    pub exec_amount: u64,
+}
+
+impl TransferReceipt {
+    /// AKA `b"transfer_receipt"`.
+    pub const SEED_PREFIX: &'static [u8; 16] = b"transfer_receipt";
+}
@@ -1,5 +1,100 @@
-use crate::OUR_CHAIN;
+use anchor_lang::prelude::*;
+use anchor_spl::token::Token;
+use const_crypto::ed25519::derive_program_address;
+use wormhole_anchor_sdk::{token_bridge, wormhole};
+
+use crate::{OUR_CHAIN, TOKEN_BRIDGE_ID, WORMHOLE_ID};
+
+/// Token Bridge config.
+pub const TOKEN_BRIDGE_CONFIG: Pubkey =
+    const_pda(&[token_bridge::Config::SEED_PREFIX], TOKEN_BRIDGE_ID);
+/// Token Bridge authority allowed to move tokens out of the sender's account.
+pub const TOKEN_BRIDGE_AUTHORITY_SIGNER: Pubkey = const_pda(
+    &[token_bridge::SEED_PREFIX_AUTHORITY_SIGNER],
+    TOKEN_BRIDGE_ID,
+);
+/// Token Bridge authority over the custody accounts of native mints.
+pub const TOKEN_BRIDGE_CUSTODY_SIGNER: Pubkey =
+    const_pda(&[token_bridge::SEED_PREFIX_CUSTODY_SIGNER], TOKEN_BRIDGE_ID);
+/// Token Bridge mint authority of wrapped mints.
+pub const TOKEN_BRIDGE_MINT_AUTHORITY: Pubkey =
+    const_pda(&[token_bridge::SEED_PREFIX_MINT_AUTHORITY], TOKEN_BRIDGE_ID);
+/// Token Bridge emitter of transfer messages.
+pub const TOKEN_BRIDGE_EMITTER: Pubkey =
+    const_pda(&[token_bridge::SEED_PREFIX_EMITTER], TOKEN_BRIDGE_ID);
+/// Wormhole sequence tracker of the Token Bridge emitter.
+pub const TOKEN_BRIDGE_SEQUENCE: Pubkey = const_pda(
+    &[
+        wormhole::SequenceTracker::SEED_PREFIX,
+        TOKEN_BRIDGE_EMITTER.as_array(),
+    ],
+    WORMHOLE_ID,
+);
+/// Wormhole Core Bridge config.
+pub const WORMHOLE_BRIDGE: Pubkey = const_pda(&[wormhole::BridgeData::SEED_PREFIX], WORMHOLE_ID);
+/// Wormhole Core Bridge fee collector.
+pub const WORMHOLE_FEE_COLLECTOR: Pubkey =
+    const_pda(&[wormhole::FeeCollector::SEED_PREFIX], WORMHOLE_ID);
+
+/// Derives a PDA at compile time, so that the Token Bridge and Wormhole
+/// accounts with static seeds are checked without hashing on chain.
+const fn const_pda(seeds: &[&[u8]], program_id: Pubkey) -> Pubkey {
+    Pubkey::new_from_array(derive_program_address(seeds, &program_id.to_bytes()).0)
+}
 
 pub fn valid_foreign_address(chain: u16, address: &[u8; 32]) -> bool {
     chain != 0 && chain != OUR_CHAIN && *address != [0; 32]
 }
+
+/// Whether the Token Bridge can bridge the mint. It only supports the SPL
+/// Token program, so Token-2022 mints, e.g. with a transfer hook or fee, are
+/// not.
+pub fn supported_mint(mint: &AccountInfo) -> bool {
+    *mint.owner == Token::id()
+}
+
+/// Derives a Token Bridge program PDA from the given seeds, for the accounts
+/// that depend on the mint or the VAA.
+pub fn token_bridge_pda(seeds: &[&[u8]]) -> Pubkey {
+    Pubkey::find_program_address(seeds, &TOKEN_BRIDGE_ID).0
+}
+
+#[cfg(test)]
+mod tests {
+    use wormhole_anchor_sdk::{token_bridge::program::TokenBridge, wormhole::program::Wormhole};
+
+    use super::*;
+
+    #[test]
+    fn program_ids_match_sdk() {
+        assert_eq!(WORMHOLE_ID, Wormhole::id());
+        assert_eq!(TOKEN_BRIDGE_ID, TokenBridge::id());
+    }
+
+    #[test]
+    fn const_pdas_match_runtime_derivation() {
+        let token_bridge_pda =
+            |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &TOKEN_BRIDGE_ID).0;
+        let wormhole_pda = |seeds: &[&[u8]]| Pubkey::find_program_address(seeds, &WORMHOLE_ID).0;
+        assert_eq!(TOKEN_BRIDGE_CONFIG, token_bridge_pda(&[b"config"]));
+        assert_eq!(
+            TOKEN_BRIDGE_AUTHORITY_SIGNER,
+            token_bridge_pda(&[b"authority_signer"])
+        );
+        assert_eq!(
+            TOKEN_BRIDGE_CUSTODY_SIGNER,
+            token_bridge_pda(&[b"custody_signer"])
+        );
+        assert_eq!(
+            TOKEN_BRIDGE_MINT_AUTHORITY,
+            token_bridge_pda(&[b"mint_signer"])
+        );
+        assert_eq!(TOKEN_BRIDGE_EMITTER, token_bridge_pda(&[b"emitter"]));
+        assert_eq!(
+            TOKEN_BRIDGE_SEQUENCE,
+            wormhole_pda(&[b"Sequence", TOKEN_BRIDGE_EMITTER.as_ref()])
+        );
+        assert_eq!(WORMHOLE_BRIDGE, wormhole_pda(&[b"Bridge"]));
+        assert_eq!(WORMHOLE_FEE_COLLECTOR, wormhole_pda(&[b"fee_collector"]));
+    }
+}
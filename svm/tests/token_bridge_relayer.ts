@@ -6,10 +6,16 @@ import { assert, expect } from "chai";
 import { BN } from "bn.js";
 import {
   getAssociatedTokenAddressSync,
+  NATIVE_MINT,
   TOKEN_PROGRAM_ID,
 } from "@solana/spl-token";
 import { serialize } from "binary-layout";
-import { signedQuoteLayout } from "./signedQuote";
+import { SignedQuote, signedQuoteLayout } from "./signedQuote";
+import {
+  postVaa,
+  serializeTransferWithPayload,
+  serializeVaaBody,
+} from "./wormhole";
 
 describe("token_bridge_relayer", () => {
   // Configure the client to use the local cluster.
@@ -67,6 +73,228 @@ describe("token_bridge_relayer", () => {
       [mint.toBuffer()],
       tokenBridgeProgram,
     )[0];
+  // the temporary token account is seeded by the Wormhole message
+  const getTmpTokenAccount = (message: anchor.web3.PublicKey) =>
+    anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("tmp"), message.toBuffer()],
+      program.programId,
+    )[0];
+  // a quote to Ethereum paid to the Wormhole fee collector that never
+  // expires, which the program accepts since it doesn't check the signature
+  const makeQuote = (overrides: Partial<SignedQuote["quote"]> = {}) =>
+    serialize(signedQuoteLayout, {
+      quote: {
+        baseFee: 0n,
+        dstChain: 2,
+        dstGasPrice: 100n,
+        dstPrice: 100n,
+        expiryTime: new Date("2200-01-01T00:00:00"),
+        payeeAddress: toHex(wormholeFeeCollector.toBuffer()),
+        prefix: "EQ01",
+        quoterAddress: "0x0000000000000000000000000000000000000000",
+        srcChain: 1,
+        srcPrice: 100n,
+        ...overrides,
+      },
+      signature:
+        "0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    });
+  // https://wormholescan.io/#/tx/0x3b06d6ae92cf1cc6312df9412be81c4cf3c1a70dad4f42a8d524db2c2f53350f?network=Testnet&view=advanced
+  // re-addressed to this program, since the resolver only accepts transfers it can redeem
+  const transferToOffset = 51 + 67; // body header + offset of `to` in the transfer
+  const executeVaaBody = (() => {
+    const vaa = Buffer.from(
+      "AQAAAAABAO8QSEQuF9qjEd5b1jfzw2U7RriEkFuA5/CeMMEi7nwmQK+GW/RS+/8SbQMl9i6iB07xp/i0e+Ps3cRif5PyNhoBZ6fepAAAAAAABgAAAAAAAAAAAAAAAGHkTlBspWWebAu6m2eFhvotcpdWAAAAAAAASnsBAwAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAKBpuIV/6rgYT7aH9jRhjANdrEOdwa6ztVmKDwAAAAAAEAAfq1IUF37mqrWWu1nwOFOzFjvJQmSYsTI/5rVHHDSJFeAAEAAAAAAAAAAAAAAACZ0h3dM0dyNj77Y66ifWVpp0cUkYNxi37Ilhe3BAaF4BvcygMhQCKYDarpE0Dgw/hAwAXv",
+      "base64",
+    );
+    const sigStart = 6;
+    const numSigners = vaa[5];
+    const sigLength = 66;
+    const body = Buffer.from(vaa.subarray(sigStart + sigLength * numSigners));
+    body.set(program.programId.toBuffer(), transferToOffset);
+    return body;
+  })();
+  // message posted by the unified transfer, relayed again further down
+  const unifiedTransferMessage = new anchor.web3.Keypair();
+  // Avalanche Fuji Token Bridge, whose endpoint is cloned from devnet
+  const fujiChain = 6;
+  const fujiTokenBridge = Buffer.from(
+    "00000000000000000000000061e44e506ca5659e6c0bba9b678586fa2d729756",
+    "hex",
+  );
+  const fujiChainBytes = Buffer.alloc(2);
+  fujiChainBytes.writeUInt16BE(fujiChain);
+  const fujiEndpoint = anchor.web3.PublicKey.findProgramAddressSync(
+    [fujiChainBytes, fujiTokenBridge],
+    tokenBridgeProgram,
+  )[0];
+  // made-up Fuji token whose wrapped mint and meta are test fixtures
+  const wrappedToken = Buffer.concat([Buffer.alloc(12), Buffer.alloc(20, 0x11)]);
+  const wrappedMint = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("wrapped"), fujiChainBytes, wrappedToken],
+    tokenBridgeProgram,
+  )[0];
+  const wrappedMeta = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("meta"), wrappedMint.toBuffer()],
+    tokenBridgeProgram,
+  )[0];
+  // posts a Token Bridge transfer from Fuji to this program for `recipient`
+  let inboundSequence = 0n;
+  const postInboundTransfer = async (transfer: {
+    amount: bigint;
+    tokenAddress: Buffer;
+    tokenChain: number;
+    recipient: anchor.web3.PublicKey;
+  }) => {
+    const sequence = ++inboundSequence;
+    const body = {
+      timestamp: Math.floor(Date.now() / 1000),
+      nonce: 0,
+      emitterChain: fujiChain,
+      emitterAddress: fujiTokenBridge,
+      sequence,
+      consistencyLevel: 1,
+      payload: serializeTransferWithPayload({
+        amount: transfer.amount,
+        tokenAddress: transfer.tokenAddress,
+        tokenChain: transfer.tokenChain,
+        to: program.programId.toBuffer(),
+        toChain: 1,
+        fromAddress: Buffer.alloc(32, 2),
+        payload: transfer.recipient.toBuffer(),
+      }),
+    };
+    const { hash, postedVaa } = await postVaa(
+      program.provider as anchor.AnchorProvider,
+      wormholeProgram,
+      body,
+    );
+    const sequenceBytes = Buffer.alloc(8);
+    sequenceBytes.writeBigUInt64BE(sequence);
+    const claim = anchor.web3.PublicKey.findProgramAddressSync(
+      [fujiTokenBridge, fujiChainBytes, sequenceBytes],
+      tokenBridgeProgram,
+    )[0];
+    return {
+      vaaHash: [...hash],
+      vaa: postedVaa,
+      vaaBody: serializeVaaBody(body),
+      claim,
+      sequence,
+    };
+  };
+  // builds an unsigned v0 transaction of the instructions using the
+  // program's LUT
+  const lutTransaction = async (
+    instructions: anchor.web3.TransactionInstruction[],
+  ) => {
+    const lutPointer = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("lut")],
+      program.programId,
+    )[0];
+    const lutAddress = (await program.account.lut.fetch(lutPointer)).address;
+    const lut =
+      await program.provider.connection.getAddressLookupTable(lutAddress);
+    if (!lut.value) {
+      throw new Error("LUT was null, did you initialize?");
+    }
+    const { blockhash } =
+      await program.provider.connection.getLatestBlockhash();
+    return new anchor.web3.VersionedTransaction(
+      new anchor.web3.TransactionMessage({
+        payerKey: program.provider.publicKey,
+        instructions: [
+          anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+            units: 1_000_000,
+          }),
+          ...instructions,
+        ],
+        recentBlockhash: blockhash,
+      }).compileToV0Message([lut.value]),
+    );
+  };
+  // sends the instructions in a v0 transaction using the program's LUT
+  const sendWithLut = async (
+    instructions: anchor.web3.TransactionInstruction[],
+    signers: anchor.web3.Signer[] = [],
+  ) => {
+    const tx = await lutTransaction(instructions);
+    tx.sign([program.provider.wallet.payer, ...signers]);
+    return program.provider.sendAndConfirm(tx);
+  };
+  // simulates the instructions in a v0 transaction using the program's LUT
+  const simulateWithLut = async (
+    instructions: anchor.web3.TransactionInstruction[],
+  ) =>
+    program.provider.connection.simulateTransaction(
+      await lutTransaction(instructions),
+      { sigVerify: false },
+    );
+  const executorProgram = new anchor.web3.PublicKey(
+    "execXUrAsMnqMmTHj5m7N1YQgsDz3cwGLYCYyuDRciV",
+  );
+  // fetches a transaction once it is confirmed
+  const getConfirmedTransaction = async (signature: string) => {
+    await program.provider.connection.confirmTransaction(
+      signature,
+      "confirmed",
+    );
+    return (await program.provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    }))!;
+  };
+  // data of the inner instructions of the transaction that invoked programId
+  const getInnerInstructions = (
+    tx: anchor.web3.VersionedTransactionResponse,
+    programId: anchor.web3.PublicKey,
+  ) => {
+    const keys = tx.transaction.message.getAccountKeys({
+      accountKeysFromLookups: tx.meta!.loadedAddresses,
+    });
+    return tx
+      .meta!.innerInstructions!.flatMap(({ instructions }) => instructions)
+      .filter((ix) => keys.get(ix.programIdIndex)!.equals(programId))
+      .map((ix) => Buffer.from(anchor.utils.bytes.bs58.decode(ix.data)));
+  };
+  // events emitted with emit_cpi!, which invokes the program itself with the
+  // event after an 8 byte tag
+  const getCpiEvents = (tx: anchor.web3.VersionedTransactionResponse) =>
+    getInnerInstructions(tx, program.programId)
+      .map((data) =>
+        program.coder.events.decode(
+          anchor.utils.bytes.base64.encode(data.subarray(8)),
+        ),
+      )
+      .filter((event) => event !== null);
+  // the arguments of the program's request_for_execution CPI
+  const getExecutorRequest = (tx: anchor.web3.VersionedTransactionResponse) => {
+    const [data] = getInnerInstructions(tx, executorProgram);
+    let offset = 8; // discriminator
+    const readBytes = (len: number) => {
+      offset += len;
+      return data.subarray(offset - len, offset);
+    };
+    const readVec = () => readBytes(readBytes(4).readUInt32LE(0));
+    return {
+      amount: readBytes(8).readBigUInt64LE(0),
+      dstChain: readBytes(2).readUInt16LE(0),
+      dstAddr: readBytes(32),
+      refundAddr: new anchor.web3.PublicKey(readBytes(32)),
+      signedQuoteBytes: readVec(),
+      requestBytes: readVec(),
+      relayInstructions: readVec(),
+    };
+  };
+  // the Executor request for a Token Bridge sequence
+  const vaaV1Request = (sequence: bigint) => {
+    const request = Buffer.alloc(46);
+    request.write("ERV1");
+    request.writeUInt16BE(1, 4);
+    tokenBridgeEmitter.toBuffer().copy(request, 6);
+    request.writeBigUInt64BE(sequence, 38);
+    return request;
+  };
 
   it("Is initialized!", async () => {
     const recentSlot = (await program.provider.connection.getSlot()) - 1;
@@ -113,20 +341,12 @@ describe("token_bridge_relayer", () => {
       "11111111111111111111111111111111",
       "SysvarC1ock11111111111111111111111111111111",
       "SysvarRent111111111111111111111111111111111",
-      "execXUrAsMnqMmTHj5m7N1YQgsDz3cwGLYCYyuDRciV",
+      executorProgram.toString(),
     ]);
   });
 
   it("Returns execute instruction!", async () => {
-    // https://wormholescan.io/#/tx/0x3b06d6ae92cf1cc6312df9412be81c4cf3c1a70dad4f42a8d524db2c2f53350f?network=Testnet&view=advanced
-    const vaa = Buffer.from(
-      "AQAAAAABAO8QSEQuF9qjEd5b1jfzw2U7RriEkFuA5/CeMMEi7nwmQK+GW/RS+/8SbQMl9i6iB07xp/i0e+Ps3cRif5PyNhoBZ6fepAAAAAAABgAAAAAAAAAAAAAAAGHkTlBspWWebAu6m2eFhvotcpdWAAAAAAAASnsBAwAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAKBpuIV/6rgYT7aH9jRhjANdrEOdwa6ztVmKDwAAAAAAEAAfq1IUF37mqrWWu1nwOFOzFjvJQmSYsTI/5rVHHDSJFeAAEAAAAAAAAAAAAAAACZ0h3dM0dyNj77Y66ifWVpp0cUkYNxi37Ilhe3BAaF4BvcygMhQCKYDarpE0Dgw/hAwAXv",
-      "base64",
-    );
-    const sigStart = 6;
-    const numSigners = vaa[5];
-    const sigLength = 66;
-    const vaa_body = vaa.subarray(sigStart + sigLength * numSigners);
+    const vaa_body = executeVaaBody;
     const vaa_hash = keccak256(`0x${vaa_body.toString("hex")}`).substring(2);
     const mint = new anchor.web3.PublicKey(
       "So11111111111111111111111111111111111111112",
@@ -135,6 +355,25 @@ describe("token_bridge_relayer", () => {
       [Buffer.from("lut")],
       program.programId,
     )[0];
+    const tokenBridgeClaim = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from(
+          "00000000000000000000000061e44e506ca5659e6c0bba9b678586fa2d729756",
+          "hex",
+        ),
+        (() => {
+          const buf = Buffer.alloc(2);
+          buf.writeUInt16BE(6);
+          return buf;
+        })(),
+        (() => {
+          const buf = Buffer.alloc(8);
+          buf.writeBigInt64BE(BigInt("19067"));
+          return buf;
+        })(),
+      ],
+      tokenBridgeProgram,
+    )[0];
     const lutPointer = await program.account.lut.fetch(lutPointerAddress);
     const first_result = await program.methods
       .resolveExecuteVaaV1(vaa_body)
@@ -146,6 +385,9 @@ describe("token_bridge_relayer", () => {
     expect(first_result.missing?.[0]?.accounts?.[1]?.toString()).to.eq(
       lutPointerAddress.toString(),
     );
+    expect(first_result.missing?.[0]?.accounts?.[2]?.toString()).to.eq(
+      tokenBridgeClaim.toString(),
+    );
     const result = await program.methods
       .resolveExecuteVaaV1(vaa_body)
       .remainingAccounts([
@@ -159,6 +401,11 @@ describe("token_bridge_relayer", () => {
           isSigner: false,
           isWritable: false,
         },
+        {
+          pubkey: tokenBridgeClaim,
+          isSigner: false,
+          isWritable: false,
+        },
       ])
       .view();
     const payer = new anchor.web3.PublicKey(
@@ -182,7 +429,7 @@ describe("token_bridge_relayer", () => {
         },
         {
           pubkey: mint.toString(),
-          isWritable: false,
+          isWritable: true,
           isSigner: false,
         },
         {
@@ -196,7 +443,10 @@ describe("token_bridge_relayer", () => {
           isSigner: false,
         },
         {
-          pubkey: "CJhdEE6bczcuVeU1m36B54oTnQYbY38rYQ1UDFCM1iY6", // tmp_token_account
+          pubkey: anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("tmp"), Buffer.from(vaa_hash, "hex")],
+            program.programId,
+          )[0].toString(), // tmp_token_account
           isWritable: true,
           isSigner: false,
         },
@@ -217,25 +467,7 @@ describe("token_bridge_relayer", () => {
           isSigner: false,
         },
         {
-          pubkey: anchor.web3.PublicKey.findProgramAddressSync(
-            [
-              Buffer.from(
-                "00000000000000000000000061e44e506ca5659e6c0bba9b678586fa2d729756",
-                "hex",
-              ),
-              (() => {
-                const buf = Buffer.alloc(2);
-                buf.writeUInt16BE(6);
-                return buf;
-              })(),
-              (() => {
-                const buf = Buffer.alloc(8);
-                buf.writeBigInt64BE(BigInt("19067"));
-                return buf;
-              })(),
-            ],
-            tokenBridgeProgram,
-          )[0].toString(), // token_bridge_claim
+          pubkey: tokenBridgeClaim.toString(), // token_bridge_claim
           isWritable: true,
           isSigner: false,
         },
@@ -270,6 +502,21 @@ describe("token_bridge_relayer", () => {
           isWritable: false,
           isSigner: false,
         },
+        {
+          pubkey: program.programId.toString(), // token_bridge_wrapped_meta (none)
+          isWritable: false,
+          isSigner: false,
+        },
+        {
+          pubkey: program.programId.toString(), // token_bridge_mint_authority (none)
+          isWritable: false,
+          isSigner: false,
+        },
+        {
+          pubkey: program.programId.toString(), // delivery_receipt (none)
+          isWritable: false,
+          isSigner: false,
+        },
         {
           pubkey: wormholeProgram.toString(),
           isWritable: false,
@@ -300,9 +547,22 @@ describe("token_bridge_relayer", () => {
           isWritable: false,
           isSigner: false,
         },
+        {
+          pubkey: anchor.web3.PublicKey.findProgramAddressSync(
+            [Buffer.from("__event_authority")],
+            program.programId,
+          )[0].toString(), // event_authority
+          isWritable: false,
+          isSigner: false,
+        },
+        {
+          pubkey: program.programId.toString(), // program
+          isWritable: false,
+          isSigner: false,
+        },
       ],
       programId: "tbr7Qje6qBzPwfM52csL5KFi8ps5c5vDyiVVBLYVdRf",
-      data: "8f51ed856cf1be9d" + vaa_hash,
+      data: "e93b37c820f7185a" + vaa_hash,
     };
     const resolvedResult = result.resolved[0][0];
     expect(resolvedResult[0].addressLookupTables[0].toString()).to.equal(
@@ -318,105 +578,1166 @@ describe("token_bridge_relayer", () => {
     expect(firstIx.data.toString("hex")).to.equal(expectedResult.data);
   });
 
-  it("transfers SOL outbound", async () => {
+  it("rejects a transfer addressed to another program", async () => {
+    const vaa_body = Buffer.from(executeVaaBody);
+    vaa_body.set(Buffer.alloc(32, 1), transferToOffset);
+    try {
+      await program.methods.resolveExecuteVaaV1(vaa_body).view();
+      assert.fail("expected the resolver to fail");
+    } catch (e) {
+      expect(e.toString()).to.contain("InvalidTransferToAddress");
+    }
+  });
+
+  it("rejects a version-prefixed relayer payload", async () => {
+    // the versioned layout is only reserved, so version 1 with no flags is
+    // still rejected
+    const vaa_body = Buffer.concat([
+      executeVaaBody.subarray(0, -32),
+      Buffer.from([1, 0]),
+      executeVaaBody.subarray(-32),
+    ]);
+    try {
+      await program.methods.resolveExecuteVaaV1(vaa_body).view();
+      assert.fail("expected the resolver to fail");
+    } catch (e) {
+      expect(e.toString()).to.contain("InvalidRelayerMessage");
+    }
+  });
+
+  it("rejects a relayer payload with trailing bytes", async () => {
+    const vaa_body = Buffer.concat([executeVaaBody, Buffer.from([0])]);
+    try {
+      await program.methods.resolveExecuteVaaV1(vaa_body).view();
+      assert.fail("expected the resolver to fail");
+    } catch (e) {
+      expect(e.toString()).to.contain("InvalidRelayerMessage");
+    }
+  });
+
+  // Token-2022 mint with a transfer hook, see tests/fixtures
+  const hookMint = new anchor.web3.PublicKey(
+    "Fo92M39oTG431eUD2ixxFep4CqzM5acdmCzgUJEACEna",
+  );
+
+  it("rejects a Token-2022 mint in the execute resolver", async () => {
+    const recipient = new anchor.web3.Keypair().publicKey;
+    const sequence = 1_000_000n;
+    const body = serializeVaaBody({
+      timestamp: 0,
+      nonce: 0,
+      emitterChain: fujiChain,
+      emitterAddress: fujiTokenBridge,
+      sequence,
+      consistencyLevel: 1,
+      payload: serializeTransferWithPayload({
+        amount: 5n,
+        tokenAddress: hookMint.toBuffer(),
+        tokenChain: 1,
+        to: program.programId.toBuffer(),
+        toChain: 1,
+        fromAddress: Buffer.alloc(32, 2),
+        payload: recipient.toBuffer(),
+      }),
+    });
+    const sequenceBytes = Buffer.alloc(8);
+    sequenceBytes.writeBigUInt64BE(sequence);
+    const tokenBridgeClaim = anchor.web3.PublicKey.findProgramAddressSync(
+      [fujiTokenBridge, fujiChainBytes, sequenceBytes],
+      tokenBridgeProgram,
+    )[0];
+    const lutPointer = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("lut")],
+      program.programId,
+    )[0];
+    try {
+      await program.methods
+        .resolveExecuteVaaV1(body)
+        .remainingAccounts(
+          [hookMint, lutPointer, tokenBridgeClaim].map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: false,
+          })),
+        )
+        .view();
+      assert.fail("expected the resolver to fail");
+    } catch (e) {
+      expect(e.toString()).to.contain("UnsupportedMint");
+    }
+  });
+
+  it("rejects a Token-2022 mint in the transfer resolver", async () => {
+    const lutPointer = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("lut")],
+      program.programId,
+    )[0];
+    try {
+      await program.methods
+        .resolveTransfer({
+          payer: program.provider.publicKey,
+          mint: hookMint,
+          wormholeMessage: null,
+          transfer: {
+            amount: new BN(10),
+            dstExecutionAddress: [...Buffer.alloc(32)],
+            dstTransferRecipient: [...Buffer.alloc(32)],
+            execAmount: { exact: [new BN(0)] },
+            nonce: 0,
+            recipientAddress: [...Buffer.alloc(32, 1)],
+            recipientChain: 2,
+            relayInstructions: { raw: [Buffer.from("")] },
+            refundAddress: null,
+            messageSeed: new BN(1),
+            minAmountOut: new BN(0),
+            signedQuoteBytes: Buffer.from(makeQuote()),
+            wrapNative: false,
+          },
+        })
+        .remainingAccounts(
+          [hookMint, lutPointer].map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: false,
+          })),
+        )
+        .view();
+      assert.fail("expected the resolver to fail");
+    } catch (e) {
+      expect(e.toString()).to.contain("UnsupportedMint");
+    }
+  });
+
+  it("Resolves the outbound transfer instruction!", async () => {
+    const mint = new anchor.web3.PublicKey(
+      "So11111111111111111111111111111111111111112",
+    );
+    const mockQuote = makeQuote();
+    const args = {
+      payer: program.provider.publicKey,
+      mint,
+      wormholeMessage: null,
+      transfer: {
+        amount: new BN(10),
+        dstExecutionAddress: [...Buffer.alloc(32)],
+        dstTransferRecipient: [...Buffer.alloc(32)],
+        execAmount: { exact: [new BN(0)] },
+        nonce: 0,
+        recipientAddress: [...Buffer.alloc(32)],
+        recipientChain: 2,
+        relayInstructions: { raw: [Buffer.from("")] },
+        refundAddress: null,
+        messageSeed: new BN(1),
+        minAmountOut: new BN(0),
+        signedQuoteBytes: Buffer.from(mockQuote),
+        wrapNative: true,
+      },
+    };
+    const lutPointerAddress = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("lut")],
+      program.programId,
+    )[0];
+    const first_result = await program.methods.resolveTransfer(args).view();
+    expect(first_result.missing?.[0]?.accounts?.[0]?.toString()).to.eq(
+      mint.toString(),
+    );
+    const result = await program.methods
+      .resolveTransfer(args)
+      .remainingAccounts([
+        { pubkey: mint, isSigner: false, isWritable: false },
+        { pubkey: lutPointerAddress, isSigner: false, isWritable: false },
+      ])
+      .view();
+    const ix = result.resolved[0][0][0].instructions[0];
+    const seed = Buffer.alloc(8);
+    seed.writeBigUInt64LE(1n);
+    const message = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("message"), program.provider.publicKey.toBuffer(), seed],
+      program.programId,
+    )[0];
+    expect(ix.programId.toString()).to.equal(program.programId.toString());
+    expect(ix.accounts[0].pubkey.toString()).to.equal(
+      program.provider.publicKey.toString(),
+    );
+    expect(ix.accounts[4].pubkey.toString()).to.equal(
+      program.programId.toString(),
+    ); // no from_token_account when wrapping SOL
+    // no keypair message, the message PDA follows it
+    expect(ix.accounts[11].pubkey.toString()).to.equal(
+      program.programId.toString(),
+    );
+    expect(ix.accounts[12].pubkey.toString()).to.equal(message.toString());
+    expect(ix.accounts[12].isSigner).to.be.false;
+    expect(ix.accounts[12].isWritable).to.be.true;
+    expect(ix.accounts[5].pubkey.toString()).to.equal(
+      getTmpTokenAccount(message).toString(),
+    );
+  });
+
+  it("Previews an outbound transfer!", async () => {
+    const mint = new anchor.web3.PublicKey(
+      "So11111111111111111111111111111111111111112",
+    );
+    const result = await program.methods
+      .previewTransfer({
+        amount: new BN(12345),
+        recipientChain: 2,
+        recipientAddress: [...Buffer.alloc(32, 1)],
+      })
+      .accountsPartial({
+        mint,
+        wormholeBridge: wormholeBridgeData,
+        tokenBridgeSequence,
+      })
+      .view();
+    // WSOL has 9 decimals, so the last digit is truncated
+    expect(result.bridgedAmount.toString()).to.equal("12340");
+    expect(result.dust.toString()).to.equal("5");
+    // the bridge config fixture charges a message fee
+    expect(result.wormholeFee.toString()).to.equal("100");
+    expect(Buffer.from(result.payload).toString("hex")).to.equal(
+      Buffer.alloc(32, 1).toString("hex"),
+    );
+    expect(Buffer.from(result.requestBytes).subarray(0, 4).toString()).to.equal(
+      "ERV1",
+    );
+  });
+
+  it("rejects a preview to this chain", async () => {
     const mint = new anchor.web3.PublicKey(
       "So11111111111111111111111111111111111111112",
     );
+    try {
+      await program.methods
+        .previewTransfer({
+          amount: new BN(12345),
+          recipientChain: 1,
+          recipientAddress: [...Buffer.alloc(32, 1)],
+        })
+        .accountsPartial({
+          mint,
+          wormholeBridge: wormholeBridgeData,
+          tokenBridgeSequence,
+        })
+        .view();
+      assert.fail("expected the preview to fail");
+    } catch (e) {
+      expect(e.toString()).to.contain("InvalidRecipient");
+    }
+  });
+
+  it("rejects a preview of a Token-2022 mint", async () => {
+    try {
+      await program.methods
+        .previewTransfer({
+          amount: new BN(12345),
+          recipientChain: 2,
+          recipientAddress: [...Buffer.alloc(32, 1)],
+        })
+        .accountsPartial({
+          mint: hookMint,
+          wormholeBridge: wormholeBridgeData,
+          tokenBridgeSequence,
+        })
+        .view();
+      assert.fail("expected the preview to fail");
+    } catch (e) {
+      expect(e.toString()).to.contain("UnsupportedMint");
+    }
+  });
+
+  // builds a transfer to Ethereum, by default of 10 lamports of wrapped SOL
+  // with the native instruction; args and accounts override the defaults
+  const transferInstruction = (
+    message: anchor.web3.PublicKey,
+    args: Partial<
+      Parameters<typeof program.methods.transferNativeTokensWithRelay>[0]
+    > = {},
+    accounts: Record<string, anchor.web3.PublicKey | null> = {},
+    unified = false,
+  ) => {
+    const transferArgs = {
+      amount: new BN(10),
+      dstExecutionAddress: [...Buffer.alloc(32)],
+      dstTransferRecipient: [...Buffer.alloc(32)],
+      execAmount: { exact: [new BN(0)] },
+      nonce: 0,
+      recipientAddress: [...Buffer.alloc(32, 1)],
+      recipientChain: 2,
+      relayInstructions: { raw: [Buffer.from("")] },
+      refundAddress: null,
+      messageSeed: null,
+      minAmountOut: new BN(0),
+      signedQuoteBytes: Buffer.from(makeQuote()),
+      wrapNative: true,
+      ...args,
+    };
+    const transferAccounts = {
+      mint: NATIVE_MINT,
+      fromTokenAccount: null,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      tokenBridgeConfig,
+      tokenBridgeCustody: getTokenBridgeCustody(NATIVE_MINT),
+      tokenBridgeAuthoritySigner,
+      tokenBridgeCustodySigner,
+      wormholeBridge: wormholeBridgeData,
+      tokenBridgeEmitter,
+      tokenBridgeSequence,
+      wormholeFeeCollector,
+      wormholeMessage: message,
+      wormholeMessagePda: null,
+      tmpTokenAccount: getTmpTokenAccount(message),
+      payee: wormholeFeeCollector,
+      transferReceipt: null,
+      clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+      rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      ...accounts,
+    };
+    return unified
+      ? program.methods
+          .transferTokensWithRelay(transferArgs)
+          .accountsPartial({
+            tokenBridgeWrappedMeta: null,
+            ...transferAccounts,
+          })
+          .instruction()
+      : program.methods
+          .transferNativeTokensWithRelay(transferArgs)
+          .accountsPartial(transferAccounts)
+          .instruction();
+  };
+
+  it("transfers SOL outbound", async () => {
+    const mint = NATIVE_MINT;
+    const message = new anchor.web3.Keypair();
+    // the next sequence is the one this transfer will be emitted with
+    const sequenceTracker =
+      await program.provider.connection.getAccountInfo(tokenBridgeSequence);
+    const sequence = sequenceTracker!.data.readBigUInt64LE(0);
+    const sequenceSerialized = Buffer.alloc(8);
+    sequenceSerialized.writeBigUInt64BE(sequence);
+    const transferReceipt = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("transfer_receipt"), sequenceSerialized],
+      program.programId,
+    )[0];
+    const ix = await transferInstruction(
+      message.publicKey,
+      {},
+      { transferReceipt },
+    );
+    // wait for lut to warm up
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    const hash = await sendWithLut([ix], [message]);
+    console.log(
+      `submitted transfer legacy tx: http://explorer.solana.com/tx/${hash}?cluster=custom&customUrl=http%3A%2F%2Flocalhost%3A8899`,
+    );
+    const receipt = await program.account.transferReceipt.fetch(
+      transferReceipt,
+    );
+    assert.equal(receipt.sequence.toString(), sequence.toString());
+    // wrapping SOL without an owner, so the payer is also the sender
+    assert.equal(
+      receipt.sender.toString(),
+      program.provider.publicKey.toString(),
+    );
+    assert.equal(
+      receipt.payer.toString(),
+      program.provider.publicKey.toString(),
+    );
+    assert.equal(receipt.mint.toString(), mint.toString());
+    assert.equal(receipt.bridgedAmount.toNumber(), 10);
+    assert.equal(receipt.recipientChain, 2);
+
+    const confirmed = await getConfirmedTransaction(hash);
+    const [event] = getCpiEvents(confirmed);
+    assert.equal(event.name, "transferInitiated");
+    assert.equal(event.data.mint.toString(), mint.toString());
+    assert.equal(event.data.amount.toNumber(), 10);
+    assert.equal(event.data.truncatedAmount.toNumber(), 10);
+    assert.equal(event.data.recipientChain, 2);
+    assert.equal(event.data.execAmount.toNumber(), 0);
+    assert.equal(event.data.emitter.toString(), tokenBridgeEmitter.toString());
+    assert.equal(event.data.sequence.toString(), sequence.toString());
+    assert.equal(
+      event.data.payer.toString(),
+      program.provider.publicKey.toString(),
+    );
+    // TransferTokensWithRelayResult
+    assert.equal(
+      confirmed.meta!.returnData!.programId,
+      program.programId.toString(),
+    );
+    const result = Buffer.from(
+      confirmed.meta!.returnData!.data[0],
+      "base64",
+    );
+    assert.equal(result.readBigUInt64LE(0), sequence);
+    assert.equal(
+      new anchor.web3.PublicKey(result.subarray(8, 40)).toString(),
+      tokenBridgeEmitter.toString(),
+    );
+    assert.equal(result.readBigUInt64LE(40), 10n);
+    assert.equal(result.readUInt32LE(48), 46);
+    assert.equal(
+      result.subarray(52).toString("hex"),
+      vaaV1Request(sequence).toString("hex"),
+    );
+    // refunds go to the payer by default
+    const request = getExecutorRequest(confirmed);
+    assert.equal(
+      request.refundAddr.toString(),
+      program.provider.publicKey.toString(),
+    );
+    assert.equal(request.dstChain, 2);
+    assert.equal(
+      request.requestBytes.toString("hex"),
+      vaaV1Request(sequence).toString("hex"),
+    );
+    await program.methods
+      .closeTransferReceipt()
+      .accounts({ transferReceipt })
+      .rpc();
+    assert.isNull(
+      await program.provider.connection.getAccountInfo(transferReceipt),
+    );
+  });
+
+  it("transfers SOL outbound with the unified instruction", async () => {
+    const message = unifiedTransferMessage;
+    const ix = await transferInstruction(message.publicKey, {}, {}, true);
+    const hash = await sendWithLut([ix], [message]);
+    console.log(
+      `submitted unified transfer tx: http://explorer.solana.com/tx/${hash}?cluster=custom&customUrl=http%3A%2F%2Flocalhost%3A8899`,
+    );
+  });
+
+  it("re-requests execution for an earlier transfer", async () => {
     const payee = anchor.web3.PublicKey.findProgramAddressSync(
       [Buffer.from("fee_collector")],
       wormholeProgram,
     )[0];
-    const mockQuote = serialize(signedQuoteLayout, {
-      quote: {
-        baseFee: 0n,
-        dstChain: 2,
-        dstGasPrice: 100n,
-        dstPrice: 100n,
-        expiryTime: new Date("2200-01-01T00:00:00"),
-        payeeAddress: toHex(payee.toBuffer()),
-        prefix: "EQ01",
-        quoterAddress: "0x0000000000000000000000000000000000000000",
-        srcChain: 1,
-        srcPrice: 100n,
-      },
-      signature:
-        "0x0000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+    const mockQuote = makeQuote();
+    // the unified transfer was the last message from the Token Bridge emitter
+    const sequenceTracker =
+      await program.provider.connection.getAccountInfo(tokenBridgeSequence);
+    const sequence = sequenceTracker!.data.readBigUInt64LE(0) - 1n;
+    const hash = await program.methods
+      .requestExecutionForSequence({
+        sequence: new BN(sequence.toString()),
+        dstExecutionAddress: [...Buffer.alloc(32)],
+        execAmount: { exact: [new BN(0)] },
+        signedQuoteBytes: Buffer.from(mockQuote),
+        relayInstructions: { raw: [Buffer.from("")] },
+        refundAddress: null,
+      })
+      .accountsPartial({
+        wormholeMessage: unifiedTransferMessage.publicKey,
+        payee,
+      })
+      .rpc({ commitment: "confirmed" });
+    const tx = await program.provider.connection.getTransaction(hash, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const requestBytes = Buffer.from(tx!.meta!.returnData!.data[0], "base64");
+    // borsh Vec<u8> length prefix
+    assert.equal(requestBytes.readUInt32LE(0), 46);
+    assert.equal(
+      requestBytes.subarray(4).toString("hex"),
+      vaaV1Request(sequence).toString("hex"),
+    );
+  });
+
+  it("rejects an expired quote", async () => {
+    const expiredQuote = makeQuote({
+      expiryTime: new Date("2000-01-01T00:00:00"),
     });
     const message = new anchor.web3.Keypair();
-    const ix = await program.methods
-      .transferNativeTokensWithRelay({
-        amount: new BN(10),
-        dstExecutionAddress: [
-          ...Buffer.from(
-            "0000000000000000000000000000000000000000000000000000000000000000",
-          ),
-        ],
-        dstTransferRecipient: [
-          ...Buffer.from(
-            "0000000000000000000000000000000000000000000000000000000000000000",
-          ),
+    const ix = await transferInstruction(message.publicKey, {
+      signedQuoteBytes: Buffer.from(expiredQuote),
+    });
+    const sim = await simulateWithLut([ix]);
+    expect(sim.value.err).to.not.be.null;
+    expect(sim.value.logs.join("\n")).to.contain("QuoteExpired");
+  });
+
+  it("rejects a transfer below the minimum amount out", async () => {
+    const message = new anchor.web3.Keypair();
+    const ix = await transferInstruction(message.publicKey, {
+      minAmountOut: new BN(11),
+    });
+    const sim = await simulateWithLut([ix]);
+    expect(sim.value.err).to.not.be.null;
+    expect(sim.value.logs.join("\n")).to.contain("InsufficientAmountOut");
+  });
+
+  it("rejects substituted Token Bridge and Wormhole accounts", async () => {
+    // the same seeds, but derived from this program instead
+    const impostor = (seed: string) =>
+      anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from(seed)],
+        program.programId,
+      )[0];
+    const cases: [Record<string, anchor.web3.PublicKey>, string][] = [
+      [{ tokenBridgeConfig: impostor("config") }, "InvalidTokenBridgeConfig"],
+      [
+        { tokenBridgeAuthoritySigner: impostor("authority_signer") },
+        "InvalidTokenBridgeAuthoritySigner",
+      ],
+      [
+        { tokenBridgeEmitter: impostor("emitter") },
+        "InvalidTokenBridgeEmitter",
+      ],
+      [
+        { wormholeFeeCollector: impostor("fee_collector") },
+        "InvalidWormholeFeeCollector",
+      ],
+    ];
+    for (const [accounts, error] of cases) {
+      const message = new anchor.web3.Keypair();
+      const ix = await transferInstruction(message.publicKey, {}, accounts);
+      const sim = await simulateWithLut([ix]);
+      expect(sim.value.err).to.not.be.null;
+      expect(sim.value.logs.join("\n")).to.contain(error);
+    }
+  });
+
+  it("encodes typed relay instructions and sends refunds to the refund address", async () => {
+    const refundAddress = new anchor.web3.Keypair().publicKey;
+    const message = new anchor.web3.Keypair();
+    const ix = await transferInstruction(message.publicKey, {
+      relayInstructions: {
+        typed: [
+          [
+            { gas: { gasLimit: new BN(250_000), msgValue: new BN(1) } },
+            {
+              gasDropOff: {
+                dropOff: new BN(2),
+                recipient: [...Buffer.alloc(32, 1)],
+              },
+            },
+          ],
         ],
-        execAmount: new BN(0),
-        nonce: 0,
-        recipientAddress: [
-          ...Buffer.from(
-            "0000000000000000000000000000000000000000000000000000000000000000",
-          ),
+      },
+      refundAddress,
+    });
+    const tx = await getConfirmedTransaction(
+      await sendWithLut([ix], [message]),
+    );
+
+    const request = getExecutorRequest(tx);
+    assert.equal(request.refundAddr.toString(), refundAddress.toString());
+    const expected = Buffer.alloc(33 + 49);
+    expected.writeUInt8(1, 0);
+    expected.writeBigUInt64BE(250_000n, 9);
+    expected.writeBigUInt64BE(1n, 25);
+    expected.writeUInt8(2, 33);
+    expected.writeBigUInt64BE(2n, 42);
+    Buffer.alloc(32, 1).copy(expected, 50);
+    assert.equal(
+      request.relayInstructions.toString("hex"),
+      expected.toString("hex"),
+    );
+  });
+
+  it("rejects typed relay instructions that drop off to another address", async () => {
+    const message = new anchor.web3.Keypair();
+    const ix = await transferInstruction(message.publicKey, {
+      relayInstructions: {
+        typed: [
+          [
+            { gas: { gasLimit: new BN(250_000), msgValue: new BN(0) } },
+            {
+              gasDropOff: {
+                dropOff: new BN(1),
+                recipient: [...Buffer.alloc(32, 2)],
+              },
+            },
+          ],
         ],
-        recipientChain: 2,
-        relayInstructions: Buffer.from(""),
-        signedQuoteBytes: Buffer.from(mockQuote),
-        wrapNative: true,
+      },
+    });
+    try {
+      await sendWithLut([ix], [message]);
+      assert.fail("expected the transfer to fail");
+    } catch (e) {
+      expect(e.toString() + e.logs?.join("\n")).to.contain(
+        "InvalidDropOffRecipient",
+      );
+    }
+  });
+
+  it("rejects typed relay instructions without a gas limit", async () => {
+    const message = new anchor.web3.Keypair();
+    const ix = await transferInstruction(message.publicKey, {
+      relayInstructions: {
+        typed: [[{ gas: { gasLimit: new BN(0), msgValue: new BN(1) } }]],
+      },
+    });
+    try {
+      await sendWithLut([ix], [message]);
+      assert.fail("expected the transfer to fail");
+    } catch (e) {
+      expect(e.toString() + e.logs?.join("\n")).to.contain("ZeroGasLimit");
+    }
+  });
+
+  it("transfers wrapped tokens outbound with the unified instruction", async () => {
+    const owner = program.provider.publicKey;
+    const fromTokenAccount = getAssociatedTokenAddressSync(wrappedMint, owner);
+    // redeem some wrapped tokens to send back
+    const redeemed = await postInboundTransfer({
+      amount: 1000n,
+      tokenAddress: wrappedToken,
+      tokenChain: fujiChain,
+      recipient: owner,
+    });
+    await program.methods
+      .completeWrappedTransferWithRelay(redeemed.vaaHash)
+      .accountsPartial({
+        tokenBridgeWrappedMint: wrappedMint,
+        recipientTokenAccount: fromTokenAccount,
+        recipient: owner,
+        tokenBridgeWrappedMeta: wrappedMeta,
+        tokenBridgeConfig,
+        vaa: redeemed.vaa,
+        tokenBridgeClaim: redeemed.claim,
+        tokenBridgeForeignEndpoint: fujiEndpoint,
+        tokenBridgeMintAuthority: tokenBridgeMintSigner,
+        deliveryReceipt: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
+      .preInstructions([
+        anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+          units: 1_000_000,
+        }),
+      ])
+      .rpc();
+
+    const message = new anchor.web3.Keypair();
+    const ix = await transferInstruction(
+      message.publicKey,
+      {
+        amount: new BN(600),
+        dstExecutionAddress: [...Buffer.alloc(32, 1)],
+        dstTransferRecipient: [...Buffer.alloc(32, 1)],
+        minAmountOut: new BN(600),
+        wrapNative: false,
+      },
+      {
+        mint: wrappedMint,
+        fromTokenAccount,
+        tokenBridgeCustody: null,
+        tokenBridgeCustodySigner: null,
+        tokenBridgeWrappedMeta: wrappedMeta,
+      },
+      true,
+    );
+    await sendWithLut([ix], [message]);
+
+    // the wrapped tokens were burned by the Token Bridge
+    const balance =
+      await program.provider.connection.getTokenAccountBalance(
+        fromTokenAccount,
+      );
+    assert.equal(balance.value.amount, "400");
+    const supply =
+      await program.provider.connection.getTokenSupply(wrappedMint);
+    assert.equal(supply.value.amount, "400");
+  });
+
+  it("records the token owner as the transfer receipt sender", async () => {
+    const owner = new anchor.web3.Keypair();
+    const fromTokenAccount = getAssociatedTokenAddressSync(
+      wrappedMint,
+      owner.publicKey,
+    );
+    const redeemed = await postInboundTransfer({
+      amount: 1000n,
+      tokenAddress: wrappedToken,
+      tokenChain: fujiChain,
+      recipient: owner.publicKey,
+    });
+    await program.methods
+      .completeWrappedTransferWithRelay(redeemed.vaaHash)
       .accountsPartial({
-        mint,
+        tokenBridgeWrappedMint: wrappedMint,
+        recipientTokenAccount: fromTokenAccount,
+        recipient: owner.publicKey,
+        tokenBridgeWrappedMeta: wrappedMeta,
+        tokenBridgeConfig,
+        vaa: redeemed.vaa,
+        tokenBridgeClaim: redeemed.claim,
+        tokenBridgeForeignEndpoint: fujiEndpoint,
+        tokenBridgeMintAuthority: tokenBridgeMintSigner,
+        deliveryReceipt: null,
         tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .preInstructions([
+        anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+          units: 1_000_000,
+        }),
+      ])
+      .rpc();
+
+    const sequenceTracker =
+      await program.provider.connection.getAccountInfo(tokenBridgeSequence);
+    const sequenceSerialized = Buffer.alloc(8);
+    sequenceSerialized.writeBigUInt64BE(
+      sequenceTracker!.data.readBigUInt64LE(0),
+    );
+    const transferReceipt = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("transfer_receipt"), sequenceSerialized],
+      program.programId,
+    )[0];
+    const message = new anchor.web3.Keypair();
+    const ix = await transferInstruction(
+      message.publicKey,
+      {
+        amount: new BN(1000),
+        dstExecutionAddress: [...Buffer.alloc(32, 1)],
+        dstTransferRecipient: [...Buffer.alloc(32, 1)],
+        wrapNative: false,
+      },
+      {
+        owner: owner.publicKey,
+        mint: wrappedMint,
+        fromTokenAccount,
+        tokenBridgeCustody: null,
+        tokenBridgeCustodySigner: null,
+        tokenBridgeWrappedMeta: wrappedMeta,
+        transferReceipt,
+      },
+      true,
+    );
+    await sendWithLut([ix], [owner, message]);
+
+    const receipt = await program.account.transferReceipt.fetch(
+      transferReceipt,
+    );
+    assert.equal(receipt.sender.toString(), owner.publicKey.toString());
+    assert.equal(
+      receipt.payer.toString(),
+      program.provider.publicKey.toString(),
+    );
+
+    // only the sender can close the receipt, reclaiming its rent
+    const closeIx = await program.methods
+      .closeTransferReceipt()
+      .accountsPartial({ sender: program.provider.publicKey, transferReceipt })
+      .instruction();
+    const { blockhash } =
+      await program.provider.connection.getLatestBlockhash();
+    const sim = await program.provider.connection.simulateTransaction(
+      new anchor.web3.VersionedTransaction(
+        new anchor.web3.TransactionMessage({
+          payerKey: program.provider.publicKey,
+          instructions: [closeIx],
+          recentBlockhash: blockhash,
+        }).compileToV0Message(),
+      ),
+      { sigVerify: false },
+    );
+    expect(sim.value.err).to.not.be.null;
+    expect(sim.value.logs.join("\n")).to.contain(
+      "InvalidTransferReceiptSender",
+    );
+    const rent = await program.provider.connection.getBalance(transferReceipt);
+    await program.methods
+      .closeTransferReceipt()
+      .accountsPartial({ sender: owner.publicKey, transferReceipt })
+      .signers([owner])
+      .rpc();
+    assert.isNull(
+      await program.provider.connection.getAccountInfo(transferReceipt),
+    );
+    assert.equal(
+      await program.provider.connection.getBalance(owner.publicKey),
+      rent,
+    );
+  });
+
+  it("skips the transfer receipt if the sequence moved on", async () => {
+    // predict a sequence that another transfer has already taken
+    const sequenceTracker =
+      await program.provider.connection.getAccountInfo(tokenBridgeSequence);
+    const sequenceSerialized = Buffer.alloc(8);
+    sequenceSerialized.writeBigUInt64BE(
+      sequenceTracker!.data.readBigUInt64LE(0) - 1n,
+    );
+    const staleReceipt = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("transfer_receipt"), sequenceSerialized],
+      program.programId,
+    )[0];
+    const message = new anchor.web3.Keypair();
+    const ix = await transferInstruction(
+      message.publicKey,
+      {},
+      { transferReceipt: staleReceipt },
+    );
+    await sendWithLut([ix], [message]);
+    assert.isNull(
+      await program.provider.connection.getAccountInfo(staleReceipt),
+    );
+  });
+
+  it("transfers with a message PDA instead of a keypair", async () => {
+    const messageSeed = 7n;
+    const seed = Buffer.alloc(8);
+    seed.writeBigUInt64LE(messageSeed);
+    const message = anchor.web3.PublicKey.findProgramAddressSync(
+      [Buffer.from("message"), program.provider.publicKey.toBuffer(), seed],
+      program.programId,
+    )[0];
+    const ix = await transferInstruction(
+      message,
+      { messageSeed: new BN(messageSeed.toString()) },
+      { wormholeMessage: null, wormholeMessagePda: message },
+    );
+    // only the payer signs
+    await sendWithLut([ix]);
+
+    // the Core Bridge posted the message to the PDA
+    const posted = await program.provider.connection.getAccountInfo(message);
+    assert.isNotNull(posted);
+    assert.equal(posted!.owner.toString(), wormholeProgram.toString());
+    assert.equal(posted!.data.subarray(0, 3).toString(), "msg");
+    // and the temporary token account was closed
+    assert.isNull(
+      await program.provider.connection.getAccountInfo(
+        getTmpTokenAccount(message),
+      ),
+    );
+  });
+
+  it("pays the Wormhole message fee from the payer", async () => {
+    // pay the Executor elsewhere, so that only the fee goes to the collector
+    const payee = new anchor.web3.Keypair().publicKey;
+    const message = new anchor.web3.Keypair();
+    const ix = await transferInstruction(
+      message.publicKey,
+      {
+        signedQuoteBytes: Buffer.from(
+          makeQuote({ payeeAddress: toHex(payee.toBuffer()) }),
+        ),
+      },
+      { payee },
+    );
+    const tx = await getConfirmedTransaction(
+      await sendWithLut([ix], [message]),
+    );
+
+    const keys = tx.transaction.message.getAccountKeys({
+      accountKeysFromLookups: tx.meta!.loadedAddresses,
+    });
+    const feeTransfers = tx
+      .meta!.innerInstructions!.flatMap(({ instructions }) => instructions)
+      .filter(
+        (ix) =>
+          keys
+            .get(ix.programIdIndex)!
+            .equals(anchor.web3.SystemProgram.programId) &&
+          keys.get(ix.accounts[1])!.equals(wormholeFeeCollector),
+      )
+      .map((ix) => ({
+        from: keys.get(ix.accounts[0])!,
+        data: Buffer.from(anchor.utils.bytes.bs58.decode(ix.data)),
+      }));
+    assert.equal(feeTransfers.length, 1);
+    assert.equal(
+      feeTransfers[0].from.toString(),
+      program.provider.publicKey.toString(),
+    );
+    // system transfer instruction with the 100 lamport fee
+    assert.equal(feeTransfers[0].data.readUInt32LE(0), 2);
+    assert.equal(feeTransfers[0].data.readBigUInt64LE(4), 100n);
+    const feeCollectorIndex = keys
+      .keySegments()
+      .flat()
+      .findIndex((key) => key.equals(wormholeFeeCollector));
+    assert.equal(
+      tx.meta!.postBalances[feeCollectorIndex] -
+        tx.meta!.preBalances[feeCollectorIndex],
+      100,
+    );
+  });
+
+  it("sends two transfers of the same mint in one transaction", async () => {
+    const sequenceTracker =
+      await program.provider.connection.getAccountInfo(tokenBridgeSequence);
+    const sequence = sequenceTracker!.data.readBigUInt64LE(0);
+    // message PDAs rather than keypairs, so both fit in one transaction
+    const messageSeeds = [8n, 9n];
+    const messages = messageSeeds.map((messageSeed) => {
+      const seed = Buffer.alloc(8);
+      seed.writeBigUInt64LE(messageSeed);
+      return anchor.web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("message"), program.provider.publicKey.toBuffer(), seed],
+        program.programId,
+      )[0];
+    });
+    const ixs = await Promise.all(
+      messages.map((message, i) =>
+        transferInstruction(
+          message,
+          { messageSeed: new BN(messageSeeds[i].toString()) },
+          { wormholeMessage: null, wormholeMessagePda: message },
+        ),
+      ),
+    );
+    await sendWithLut(ixs);
+
+    const after =
+      await program.provider.connection.getAccountInfo(tokenBridgeSequence);
+    assert.equal(after!.data.readBigUInt64LE(0), sequence + 2n);
+    for (const message of messages) {
+      // each transfer posted its own message and closed its own temporary
+      // token account
+      const posted = await program.provider.connection.getAccountInfo(message);
+      assert.equal(posted!.owner.toString(), wormholeProgram.toString());
+      assert.isNull(
+        await program.provider.connection.getAccountInfo(
+          getTmpTokenAccount(message),
+        ),
+      );
+    }
+  });
+
+  it("completes a native transfer with the unified instruction", async () => {
+    const recipient = new anchor.web3.Keypair().publicKey;
+    // one normalized unit of wrapped SOL, held in custody by earlier transfers
+    const redeemed = await postInboundTransfer({
+      amount: 1n,
+      tokenAddress: NATIVE_MINT.toBuffer(),
+      tokenChain: 1,
+      recipient,
+    });
+    const hash = await program.methods
+      .completeTransferWithRelay(redeemed.vaaHash)
+      .accountsPartial({
+        mint: NATIVE_MINT,
+        recipientTokenAccount: getAssociatedTokenAddressSync(
+          NATIVE_MINT,
+          recipient,
+        ),
+        recipient,
         tokenBridgeConfig,
-        tokenBridgeCustody: getTokenBridgeCustody(mint),
-        tokenBridgeAuthoritySigner,
+        vaa: redeemed.vaa,
+        tokenBridgeClaim: redeemed.claim,
+        tokenBridgeForeignEndpoint: fujiEndpoint,
+        tokenBridgeCustody: getTokenBridgeCustody(NATIVE_MINT),
         tokenBridgeCustodySigner,
-        wormholeBridge: wormholeBridgeData,
-        tokenBridgeEmitter,
-        tokenBridgeSequence,
-        wormholeFeeCollector,
-        wormholeMessage: message.publicKey,
-        payee: payee,
-        clock: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+        tokenBridgeWrappedMeta: null,
+        tokenBridgeMintAuthority: null,
+        deliveryReceipt: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
         rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
-      .instruction();
-    // console.log(ix.keys.map((k) => k.pubkey.toString()));
-    // wait for lut to warm up
-    await new Promise((resolve) => setTimeout(resolve, 2000));
+      .preInstructions([
+        anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+          units: 1_000_000,
+        }),
+      ])
+      .rpc({ commitment: "confirmed" });
+
+    // wrapped SOL is unwrapped by closing the temporary token account to the
+    // recipient, so it also receives that account's rent
+    const tmpRent =
+      await program.provider.connection.getMinimumBalanceForRentExemption(165);
+    assert.equal(
+      await program.provider.connection.getBalance(recipient),
+      tmpRent + 10,
+    );
+
+    const tx = await getConfirmedTransaction(hash);
+    const [event] = getCpiEvents(tx);
+    assert.equal(event.name, "transferRedeemed");
+    assert.equal(event.data.emitterChain, fujiChain);
+    assert.equal(
+      Buffer.from(event.data.emitterAddress).toString("hex"),
+      fujiTokenBridge.toString("hex"),
+    );
+    assert.equal(event.data.sequence.toString(), redeemed.sequence.toString());
+    assert.equal(event.data.mint.toString(), NATIVE_MINT.toString());
+    assert.equal(event.data.amount.toNumber(), 10);
+    assert.equal(event.data.recipient.toString(), recipient.toString());
+    assert.isTrue(event.data.unwrapped);
+    assert.equal(
+      event.data.payer.toString(),
+      program.provider.publicKey.toString(),
+    );
+    // CompleteTransferWithRelayResult
+    const result = Buffer.from(tx.meta!.returnData!.data[0], "base64");
+    assert.equal(result.readBigUInt64LE(0), 10n);
+  });
+
+  it("completes a wrapped transfer with the unified instruction", async () => {
+    const recipient = new anchor.web3.Keypair().publicKey;
+    const recipientTokenAccount = getAssociatedTokenAddressSync(
+      wrappedMint,
+      recipient,
+    );
+    const redeemed = await postInboundTransfer({
+      amount: 250n,
+      tokenAddress: wrappedToken,
+      tokenChain: fujiChain,
+      recipient,
+    });
+    await program.methods
+      .completeTransferWithRelay(redeemed.vaaHash)
+      .accountsPartial({
+        mint: wrappedMint,
+        recipientTokenAccount,
+        recipient,
+        tokenBridgeConfig,
+        vaa: redeemed.vaa,
+        tokenBridgeClaim: redeemed.claim,
+        tokenBridgeForeignEndpoint: fujiEndpoint,
+        tokenBridgeCustody: null,
+        tokenBridgeCustodySigner: null,
+        tokenBridgeWrappedMeta: wrappedMeta,
+        tokenBridgeMintAuthority: tokenBridgeMintSigner,
+        deliveryReceipt: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .preInstructions([
+        anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+          units: 1_000_000,
+        }),
+      ])
+      .rpc();
+
+    const balance =
+      await program.provider.connection.getTokenAccountBalance(
+        recipientTokenAccount,
+      );
+    assert.equal(balance.value.amount, "250");
+  });
+
+  it("resolves nothing for an already redeemed transfer", async () => {
+    const recipient = new anchor.web3.Keypair().publicKey;
+    const redeemed = await postInboundTransfer({
+      amount: 50n,
+      tokenAddress: wrappedToken,
+      tokenChain: fujiChain,
+      recipient,
+    });
     const lutPointer = anchor.web3.PublicKey.findProgramAddressSync(
       [Buffer.from("lut")],
       program.programId,
     )[0];
-    const lutAddress = (await program.account.lut.fetch(lutPointer)).address;
-    const lut =
-      await program.provider.connection.getAddressLookupTable(lutAddress);
-    if (!lut.value) {
-      throw new Error("LUT was null, did you initialize?");
-    }
-    const { blockhash } =
-      await program.provider.connection.getLatestBlockhash();
-    const messageV0 = new anchor.web3.TransactionMessage({
-      payerKey: program.provider.publicKey,
-      instructions: [
+    const resolve = () =>
+      program.methods
+        .resolveExecuteVaaV1(redeemed.vaaBody)
+        .remainingAccounts(
+          [wrappedMint, lutPointer, redeemed.claim].map((pubkey) => ({
+            pubkey,
+            isSigner: false,
+            isWritable: false,
+          })),
+        )
+        .view();
+    const before = await resolve();
+    assert.equal(before.resolved[0][0].length, 1);
+
+    // redeemed by someone else, e.g. the recipient themselves
+    await program.methods
+      .completeTransferWithRelay(redeemed.vaaHash)
+      .accountsPartial({
+        mint: wrappedMint,
+        recipientTokenAccount: getAssociatedTokenAddressSync(
+          wrappedMint,
+          recipient,
+        ),
+        recipient,
+        tokenBridgeConfig,
+        vaa: redeemed.vaa,
+        tokenBridgeClaim: redeemed.claim,
+        tokenBridgeForeignEndpoint: fujiEndpoint,
+        tokenBridgeCustody: null,
+        tokenBridgeCustodySigner: null,
+        tokenBridgeWrappedMeta: wrappedMeta,
+        tokenBridgeMintAuthority: tokenBridgeMintSigner,
+        deliveryReceipt: null,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .preInstructions([
         anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
           units: 1_000_000,
         }),
-        ix,
+      ])
+      .rpc({ commitment: "confirmed" });
+
+    const after = await resolve();
+    expect(after.resolved[0][0]).to.deep.equal([]);
+  });
+
+  it("writes a delivery receipt and closes it", async () => {
+    const recipient = new anchor.web3.Keypair().publicKey;
+    const redeemed = await postInboundTransfer({
+      amount: 100n,
+      tokenAddress: wrappedToken,
+      tokenChain: fujiChain,
+      recipient,
+    });
+    const sequenceBytes = Buffer.alloc(8);
+    sequenceBytes.writeBigUInt64BE(redeemed.sequence);
+    const deliveryReceipt = anchor.web3.PublicKey.findProgramAddressSync(
+      [
+        Buffer.from("delivery_receipt"),
+        fujiChainBytes,
+        fujiTokenBridge,
+        sequenceBytes,
       ],
-      recentBlockhash: blockhash,
-    }).compileToV0Message([lut.value]);
-    const tx = new anchor.web3.VersionedTransaction(messageV0);
-    // console.log(tx.message.staticAccountKeys, tx.message.addressTableLookups);
-    tx.sign([program.provider.wallet.payer, message]);
-    const hash = await program.provider.sendAndConfirm(tx);
-    console.log(
-      `submitted transfer legacy tx: http://explorer.solana.com/tx/${hash}?cluster=custom&customUrl=http%3A%2F%2Flocalhost%3A8899`,
+      program.programId,
+    )[0];
+    await program.methods
+      .completeWrappedTransferWithRelay(redeemed.vaaHash)
+      .accountsPartial({
+        tokenBridgeWrappedMint: wrappedMint,
+        recipientTokenAccount: getAssociatedTokenAddressSync(
+          wrappedMint,
+          recipient,
+        ),
+        recipient,
+        tokenBridgeWrappedMeta: wrappedMeta,
+        tokenBridgeConfig,
+        vaa: redeemed.vaa,
+        tokenBridgeClaim: redeemed.claim,
+        tokenBridgeForeignEndpoint: fujiEndpoint,
+        tokenBridgeMintAuthority: tokenBridgeMintSigner,
+        deliveryReceipt,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
+      })
+      .preInstructions([
+        anchor.web3.ComputeBudgetProgram.setComputeUnitLimit({
+          units: 1_000_000,
+        }),
+      ])
+      .rpc();
+
+    const receipt = await program.account.deliveryReceipt.fetch(
+      deliveryReceipt,
+    );
+    assert.equal(receipt.emitterChain, fujiChain);
+    assert.deepEqual(Buffer.from(receipt.emitterAddress), fujiTokenBridge);
+    assert.equal(receipt.sequence.toString(), redeemed.sequence.toString());
+    assert.isTrue(receipt.payer.equals(program.provider.publicKey));
+    assert.isTrue(receipt.recipient.equals(recipient));
+    assert.equal(receipt.amount.toNumber(), 100);
+    assert.isAbove(receipt.slot.toNumber(), 0);
+
+    await program.methods
+      .closeDeliveryReceipt()
+      .accountsPartial({ deliveryReceipt })
+      .rpc();
+    assert.isNull(
+      await program.provider.connection.getAccountInfo(deliveryReceipt),
     );
-    // TODO: check the receipt and ensure the accurate token balances changed
   });
 });
@@ -0,0 +1,184 @@
+import * as anchor from "@coral-xyz/anchor";
+import { keccak256, toBytes } from "viem";
+import { privateKeyToAddress, sign } from "viem/accounts";
+
+// Tilt devnet guardian, installed as guardian set 0 by
+// tests/fixtures/guardian_set_0.json.
+const GUARDIAN_PRIVATE_KEY =
+  "0xcfb12303a19cde580bb4dd771639b0d26bc68353645571a8cff516ab2ee113a0";
+
+// Core Bridge instruction indices.
+const POST_VAA = 2;
+const VERIFY_SIGNATURES = 7;
+
+export type VaaBody = {
+  timestamp: number;
+  nonce: number;
+  emitterChain: number;
+  emitterAddress: Buffer;
+  sequence: bigint;
+  consistencyLevel: number;
+  payload: Buffer;
+};
+
+export function serializeVaaBody(body: VaaBody): Buffer {
+  const header = Buffer.alloc(51);
+  header.writeUInt32BE(body.timestamp, 0);
+  header.writeUInt32BE(body.nonce, 4);
+  header.writeUInt16BE(body.emitterChain, 8);
+  body.emitterAddress.copy(header, 10);
+  header.writeBigUInt64BE(body.sequence, 42);
+  header.writeUInt8(body.consistencyLevel, 50);
+  return Buffer.concat([header, body.payload]);
+}
+
+export type TransferWithPayload = {
+  amount: bigint;
+  tokenAddress: Buffer;
+  tokenChain: number;
+  to: Buffer;
+  toChain: number;
+  fromAddress: Buffer;
+  payload: Buffer;
+};
+
+// Token Bridge payload ID 3.
+export function serializeTransferWithPayload(
+  transfer: TransferWithPayload,
+): Buffer {
+  const out = Buffer.alloc(133);
+  out.writeUInt8(3, 0);
+  out.set(toBytes(transfer.amount, { size: 32 }), 1);
+  transfer.tokenAddress.copy(out, 33);
+  out.writeUInt16BE(transfer.tokenChain, 65);
+  transfer.to.copy(out, 67);
+  out.writeUInt16BE(transfer.toChain, 99);
+  transfer.fromAddress.copy(out, 101);
+  return Buffer.concat([out, transfer.payload]);
+}
+
+// Signs the body with the devnet guardian and posts it to the Core Bridge.
+// Returns the VAA hash and the posted VAA account.
+export async function postVaa(
+  provider: anchor.AnchorProvider,
+  wormholeProgram: anchor.web3.PublicKey,
+  body: VaaBody,
+): Promise<{ hash: Buffer; postedVaa: anchor.web3.PublicKey }> {
+  const serialized = serializeVaaBody(body);
+  const hash = Buffer.from(toBytes(keccak256(serialized)));
+  // guardians sign the double hash, while the secp256k1 program hashes the
+  // message it is given once more
+  const signature = await sign({
+    hash: keccak256(hash),
+    privateKey: GUARDIAN_PRIVATE_KEY,
+  });
+
+  const guardianSetIndex = Buffer.alloc(4);
+  const guardianSet = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("GuardianSet"), guardianSetIndex],
+    wormholeProgram,
+  )[0];
+  const bridge = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("Bridge")],
+    wormholeProgram,
+  )[0];
+  const postedVaa = anchor.web3.PublicKey.findProgramAddressSync(
+    [Buffer.from("PostedVAA"), hash],
+    wormholeProgram,
+  )[0];
+  const signatureSet = anchor.web3.Keypair.generate();
+
+  // the only guardian signed, as the first secp256k1 signature
+  const signers = Buffer.alloc(19, 0xff);
+  signers.writeInt8(0, 0);
+  await provider.sendAndConfirm(
+    new anchor.web3.Transaction().add(
+      anchor.web3.Secp256k1Program.createInstructionWithEthAddress({
+        ethAddress: privateKeyToAddress(GUARDIAN_PRIVATE_KEY),
+        message: hash,
+        signature: Buffer.concat([
+          toBytes(signature.r, { size: 32 }),
+          toBytes(signature.s, { size: 32 }),
+        ]),
+        recoveryId: signature.yParity!,
+      }),
+      new anchor.web3.TransactionInstruction({
+        programId: wormholeProgram,
+        keys: [
+          { pubkey: provider.publicKey, isSigner: true, isWritable: true },
+          { pubkey: guardianSet, isSigner: false, isWritable: false },
+          {
+            pubkey: signatureSet.publicKey,
+            isSigner: true,
+            isWritable: true,
+          },
+          {
+            pubkey: anchor.web3.SYSVAR_INSTRUCTIONS_PUBKEY,
+            isSigner: false,
+            isWritable: false,
+          },
+          {
+            pubkey: anchor.web3.SYSVAR_RENT_PUBKEY,
+            isSigner: false,
+            isWritable: false,
+          },
+          {
+            pubkey: anchor.web3.SystemProgram.programId,
+            isSigner: false,
+            isWritable: false,
+          },
+        ],
+        data: Buffer.concat([Buffer.from([VERIFY_SIGNATURES]), signers]),
+      }),
+    ),
+    [signatureSet],
+  );
+
+  const data = Buffer.alloc(61);
+  data.writeUInt8(POST_VAA, 0);
+  data.writeUInt8(1, 1); // version
+  guardianSetIndex.copy(data, 2);
+  data.writeUInt32LE(body.timestamp, 6);
+  data.writeUInt32LE(body.nonce, 10);
+  data.writeUInt16LE(body.emitterChain, 14);
+  body.emitterAddress.copy(data, 16);
+  data.writeBigUInt64LE(body.sequence, 48);
+  data.writeUInt8(body.consistencyLevel, 56);
+  data.writeUInt32LE(body.payload.length, 57);
+  await provider.sendAndConfirm(
+    new anchor.web3.Transaction().add(
+      new anchor.web3.TransactionInstruction({
+        programId: wormholeProgram,
+        keys: [
+          { pubkey: guardianSet, isSigner: false, isWritable: false },
+          { pubkey: bridge, isSigner: false, isWritable: false },
+          {
+            pubkey: signatureSet.publicKey,
+            isSigner: false,
+            isWritable: false,
+          },
+          { pubkey: postedVaa, isSigner: false, isWritable: true },
+          { pubkey: provider.publicKey, isSigner: true, isWritable: true },
+          {
+            pubkey: anchor.web3.SYSVAR_CLOCK_PUBKEY,
+            isSigner: false,
+            isWritable: false,
+          },
+          {
+            pubkey: anchor.web3.SYSVAR_RENT_PUBKEY,
+            isSigner: false,
+            isWritable: false,
+          },
+          {
+            pubkey: anchor.web3.SystemProgram.programId,
+            isSigner: false,
+            isWritable: false,
+          },
+        ],
+        data: Buffer.concat([data, body.payload]),
+      }),
+    ),
+  );
+
+  return { hash, postedVaa };
+}
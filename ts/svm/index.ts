@@ -40,12 +40,17 @@ export async function transfer(
       wrapNative: true,
       dstTransferRecipient,
       dstExecutionAddress,
-      execAmount: new BN(execAmount),
+      execAmount: { exact: [new BN(execAmount)] },
       signedQuoteBytes,
-      relayInstructions,
+      relayInstructions: { raw: [relayInstructions] },
+      refundAddress: null,
+      messageSeed: null,
+      minAmountOut: new BN(0),
     })
     .accountsPartial({
       mint,
+      // wrapping native SOL, so no source token account is needed
+      fromTokenAccount: null,
       tokenBridgeConfig: web3.PublicKey.findProgramAddressSync(
         [Buffer.from("config")],
         tokenBridgeProgram,
@@ -66,6 +71,11 @@ export async function transfer(
         "6bi4JGDoRwUs9TYBuvoA7dUVyikTJDrJsJU1ew6KVLiu",
       ),
       wormholeMessage: messageKeypair.publicKey,
+      wormholeMessagePda: null,
+      tmpTokenAccount: web3.PublicKey.findProgramAddressSync(
+        [Buffer.from("tmp"), messageKeypair.publicKey.toBuffer()],
+        program.programId,
+      )[0],
       tokenBridgeEmitter,
       tokenBridgeSequence: web3.PublicKey.findProgramAddressSync(
         [Buffer.from("Sequence"), tokenBridgeEmitter.toBytes()],
@@ -75,6 +85,7 @@ export async function transfer(
         "7s3a1ycs16d6SNDumaRtjcoyMaTDZPavzgsmS3uUZYWX",
       ),
       payee: new web3.PublicKey(signedQuoteBytes.slice(24, 56)),
+      transferReceipt: null,
       wormholeProgram,
       tokenBridgeProgram,
       clock: web3.SYSVAR_CLOCK_PUBKEY,
@@ -166,6 +177,7 @@ export async function redeem(
         [Buffer.from("custody_signer")],
         tokenBridgeProgram,
       )[0],
+      deliveryReceipt: null,
       wormholeProgram,
       tokenBridgeProgram,
       rent: web3.SYSVAR_RENT_PUBKEY,